@@ -0,0 +1,86 @@
+//! Runtime API definition for the proof of existence pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+
+/// A claim as returned by the runtime.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+pub struct ClaimDetails<AccountId, Balance, BlockNumber> {
+	/// The current owner of the claim.
+	pub owner: AccountId,
+	/// The amount reserved from the owner for storing the claim.
+	pub deposit: Balance,
+	/// The block the claim was created in.
+	pub created_at: BlockNumber,
+	/// The block the claim was last transferred in, or the creation block.
+	pub transferred_at: BlockNumber,
+	/// A human readable description of the notarized content.
+	pub description: Vec<u8>,
+	/// The MIME type of the notarized content.
+	pub content_type: Vec<u8>,
+	/// Where the notarized content can be retrieved from.
+	pub uri: Option<Vec<u8>>,
+}
+
+/// What happened to a claim in a history entry.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum ClaimAction {
+	Created,
+	Transferred,
+	Revoked,
+	Expired,
+}
+
+/// A change of ownership in the history of a claim.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct HistoryEntry<AccountId, BlockNumber> {
+	/// The owner before the change, `None` when the claim was created.
+	pub from: Option<AccountId>,
+	/// The owner after the change, `None` when the claim was removed.
+	pub to: Option<AccountId>,
+	/// The block the change happened in.
+	pub block: BlockNumber,
+	/// What happened to the claim.
+	pub action: ClaimAction,
+}
+
+/// The provenance of a claim as returned to clients.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum ClaimHistory<AccountId, BlockNumber> {
+	/// The ownership changes of a live claim, oldest first.
+	Active(Vec<HistoryEntry<AccountId, BlockNumber>>),
+	/// The claim was removed. Only the entry recording its removal is kept.
+	Tombstone(HistoryEntry<AccountId, BlockNumber>),
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait PoeApi<AccountId, Hash, Balance, BlockNumber> where
+		AccountId: Codec,
+		Hash: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// The claim notarizing the content with digest `claim`, if any.
+		fn claim_of(claim: Hash) -> Option<ClaimDetails<AccountId, Balance, BlockNumber>>;
+
+		/// All claims owned by `owner`.
+		fn claims_of(owner: AccountId) -> Vec<Hash>;
+
+		/// Check that `leaf` belongs to the batch notarized by the Merkle claim `root`.
+		fn verify_inclusion(root: Hash, leaf: Hash, proof: Vec<Hash>) -> bool;
+
+		/// The ownership changes of `claim`, or its tombstone if it was removed.
+		fn claim_history(claim: Hash) -> Option<ClaimHistory<AccountId, BlockNumber>>;
+	}
+}
@@ -0,0 +1,152 @@
+//! RPC interface for the proof of existence pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use pallet_poe_rpc_runtime_api::ClaimDetails as RuntimeClaimDetails;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_poe_rpc_runtime_api::{ClaimHistory, PoeApi as PoeRuntimeApi};
+
+/// A claim as returned to clients. The deposit is a number or hex string so that balances
+/// wider than a JSON number survive, and the metadata is hex encoded.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimDetails<AccountId, BlockNumber> {
+	/// The current owner of the claim.
+	pub owner: AccountId,
+	/// The amount reserved from the owner for storing the claim.
+	pub deposit: NumberOrHex,
+	/// The block the claim was created in.
+	pub created_at: BlockNumber,
+	/// The block the claim was last transferred in, or the creation block.
+	pub transferred_at: BlockNumber,
+	/// A human readable description of the notarized content.
+	pub description: Bytes,
+	/// The MIME type of the notarized content.
+	pub content_type: Bytes,
+	/// Where the notarized content can be retrieved from.
+	pub uri: Option<Bytes>,
+}
+
+impl<AccountId, Balance, BlockNumber> From<RuntimeClaimDetails<AccountId, Balance, BlockNumber>>
+	for ClaimDetails<AccountId, BlockNumber>
+where
+	Balance: Into<NumberOrHex>,
+{
+	fn from(claim: RuntimeClaimDetails<AccountId, Balance, BlockNumber>) -> Self {
+		Self {
+			owner: claim.owner,
+			deposit: claim.deposit.into(),
+			created_at: claim.created_at,
+			transferred_at: claim.transferred_at,
+			description: claim.description.into(),
+			content_type: claim.content_type.into(),
+			uri: claim.uri.map(Into::into),
+		}
+	}
+}
+
+/// Claim lookups that would otherwise require building `Proofs` storage keys.
+#[rpc]
+pub trait PoeApi<BlockHash, AccountId, Hash, Balance, BlockNumber> {
+	/// The claim notarizing the content with digest `claim`, if any.
+	#[rpc(name = "poe_getClaim")]
+	fn get_claim(
+		&self,
+		claim: Hash,
+		at: Option<BlockHash>,
+	) -> Result<Option<ClaimDetails<AccountId, BlockNumber>>>;
+
+	/// All claims owned by `owner`.
+	#[rpc(name = "poe_getClaimsByOwner")]
+	fn get_claims_by_owner(&self, owner: AccountId, at: Option<BlockHash>) -> Result<Vec<Hash>>;
+
+	/// The ownership changes of `claim`, or its tombstone if it was removed.
+	#[rpc(name = "poe_getClaimHistory")]
+	fn get_claim_history(
+		&self,
+		claim: Hash,
+		at: Option<BlockHash>,
+	) -> Result<Option<ClaimHistory<AccountId, BlockNumber>>>;
+}
+
+/// Error code returned when the runtime API call fails.
+const RUNTIME_ERROR: i64 = 1;
+
+/// Implements the `PoeApi` RPC trait by calling into the runtime.
+pub struct Poe<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Poe<C, B> {
+	/// Create a new `Poe` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, AccountId, Hash, Balance, BlockNumber>
+	PoeApi<<Block as BlockT>::Hash, AccountId, Hash, Balance, BlockNumber> for Poe<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: PoeRuntimeApi<Block, AccountId, Hash, Balance, BlockNumber>,
+	AccountId: Codec,
+	Hash: Codec,
+	Balance: Codec + Into<NumberOrHex>,
+	BlockNumber: Codec,
+{
+	fn get_claim(
+		&self,
+		claim: Hash,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<ClaimDetails<AccountId, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.claim_of(&at, claim)
+			.map(|claim| claim.map(Into::into))
+			.map_err(|e| runtime_error("Unable to query claim.", e))
+	}
+
+	fn get_claims_by_owner(
+		&self,
+		owner: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<Hash>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.claims_of(&at, owner)
+			.map_err(|e| runtime_error("Unable to query claims.", e))
+	}
+
+	fn get_claim_history(
+		&self,
+		claim: Hash,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<ClaimHistory<AccountId, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.claim_history(&at, claim)
+			.map_err(|e| runtime_error("Unable to query claim history.", e))
+	}
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
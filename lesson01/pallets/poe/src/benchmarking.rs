@@ -0,0 +1,240 @@
+//! Benchmarking setup for pallet-poe
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as PoeModule;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::traits::{Currency, Get, Hooks};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, Hash};
+use sp_std::{vec, vec::Vec};
+
+fn funded<T: Config>(who: T::AccountId) -> T::AccountId {
+	T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 2u32.into());
+	who
+}
+
+fn claim_of<T: Config>(index: u32) -> T::Hash {
+	T::Hashing::hash_of(&index)
+}
+
+fn create<T: Config>(owner: &T::AccountId, claim: T::Hash) {
+	PoeModule::<T>::create_claim(RawOrigin::Signed(owner.clone()).into(), claim)
+		.expect("claim can be created");
+}
+
+benchmarks! {
+	create_claim {
+		let caller = funded::<T>(whitelisted_caller());
+		let claim = claim_of::<T>(0);
+	}: _(RawOrigin::Signed(caller.clone()), claim)
+	verify {
+		assert_eq!(Proofs::<T>::get(&claim).map(|info| info.owner), Some(caller));
+	}
+
+	create_merkle_claim {
+		let caller = funded::<T>(whitelisted_caller());
+		let root = claim_of::<T>(0);
+	}: _(RawOrigin::Signed(caller), root, u32::MAX)
+	verify {
+		assert_eq!(MerkleClaims::<T>::get(&root), Some(u32::MAX));
+	}
+
+	create_expiring_claim {
+		let caller = funded::<T>(whitelisted_caller());
+		let claim = claim_of::<T>(0);
+		let expires_at = frame_system::Pallet::<T>::block_number() + 10u32.into();
+	}: _(RawOrigin::Signed(caller), claim, expires_at)
+	verify {
+		assert_eq!(ClaimExpiry::<T>::get(&claim), Some(expires_at));
+	}
+
+	renew_claim {
+		let caller = funded::<T>(whitelisted_caller());
+		let claim = claim_of::<T>(0);
+		let expires_at = frame_system::Pallet::<T>::block_number() + 10u32.into();
+		PoeModule::<T>::create_expiring_claim(
+			RawOrigin::Signed(caller.clone()).into(),
+			claim,
+			expires_at,
+		)?;
+		let renewed = expires_at + 10u32.into();
+	}: _(RawOrigin::Signed(caller), claim, renewed)
+	verify {
+		assert_eq!(ClaimExpiry::<T>::get(&claim), Some(renewed));
+	}
+
+	on_initialize {
+		let c in 0 .. T::MaxExpiriesPerBlock::get();
+
+		let expires_at = frame_system::Pallet::<T>::block_number() + 1u32.into();
+		for i in 0 .. c {
+			let owner = funded::<T>(account("owner", i, 0));
+			PoeModule::<T>::create_expiring_claim(
+				RawOrigin::Signed(owner).into(),
+				claim_of::<T>(i),
+				expires_at,
+			)?;
+		}
+	}: {
+		PoeModule::<T>::on_initialize(expires_at);
+	}
+	verify {
+		assert!(ExpirySchedule::<T>::get(expires_at).is_empty());
+	}
+
+	propose_claim {
+		let a in 1 .. T::MaxAttesters::get();
+
+		let caller = funded::<T>(whitelisted_caller());
+		let claim = claim_of::<T>(0);
+		let attesters: Vec<T::AccountId> = (0 .. a).map(|i| account("attester", i, 0)).collect();
+	}: _(RawOrigin::Signed(caller), claim, attesters, a)
+	verify {
+		assert!(PendingClaims::<T>::contains_key(&claim));
+	}
+
+	attest_claim {
+		let a in 1 .. T::MaxAttesters::get();
+
+		let proposer = funded::<T>(account("proposer", 0, 0));
+		let claim = claim_of::<T>(0);
+		let attesters: Vec<T::AccountId> = (0 .. a).map(|i| account("attester", i, 0)).collect();
+		PoeModule::<T>::propose_claim(
+			RawOrigin::Signed(proposer.clone()).into(),
+			claim,
+			attesters.clone(),
+			a,
+		)?;
+		for attester in &attesters[1..] {
+			PoeModule::<T>::attest_claim(RawOrigin::Signed(attester.clone()).into(), claim)?;
+		}
+	}: _(RawOrigin::Signed(attesters[0].clone()), claim)
+	verify {
+		assert_eq!(Proofs::<T>::get(&claim).map(|info| info.owner), Some(proposer));
+	}
+
+	cancel_proposal {
+		let caller = funded::<T>(whitelisted_caller());
+		let claim = claim_of::<T>(0);
+		PoeModule::<T>::propose_claim(
+			RawOrigin::Signed(caller.clone()).into(),
+			claim,
+			vec![account("attester", 0, 0)],
+			1,
+		)?;
+	}: _(RawOrigin::Signed(caller), claim)
+	verify {
+		assert!(!PendingClaims::<T>::contains_key(&claim));
+	}
+
+	approve_operator {
+		let caller: T::AccountId = whitelisted_caller();
+		let operator: T::AccountId = account("operator", 0, 0);
+	}: _(RawOrigin::Signed(caller.clone()), operator.clone(), Permissions::ALL)
+	verify {
+		assert_eq!(Operators::<T>::get(&caller, &operator), Some(Permissions::ALL));
+	}
+
+	remove_operator {
+		let caller: T::AccountId = whitelisted_caller();
+		let operator: T::AccountId = account("operator", 0, 0);
+		Operators::<T>::insert(&caller, &operator, Permissions::ALL);
+	}: _(RawOrigin::Signed(caller.clone()), operator.clone())
+	verify {
+		assert!(!Operators::<T>::contains_key(&caller, &operator));
+	}
+
+	revoke_claim {
+		let caller = funded::<T>(whitelisted_caller());
+		let claim = claim_of::<T>(0);
+		create::<T>(&caller, claim);
+	}: _(RawOrigin::Signed(caller), claim)
+	verify {
+		assert!(!Proofs::<T>::contains_key(&claim));
+	}
+
+	revoke_all_claims {
+		let c in 1 .. T::MaxClaimsPerAccount::get();
+
+		let caller = funded::<T>(whitelisted_caller());
+		for i in 0 .. c {
+			create::<T>(&caller, claim_of::<T>(i));
+		}
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(AccountClaimCount::<T>::get(&caller), 0);
+	}
+
+	offer_claim {
+		let caller = funded::<T>(whitelisted_caller());
+		let dest: T::AccountId = account("dest", 0, 0);
+		let claim = claim_of::<T>(0);
+		create::<T>(&caller, claim);
+	}: _(RawOrigin::Signed(caller), claim, dest, None)
+	verify {
+		assert!(PendingTransfers::<T>::contains_key(&claim));
+	}
+
+	accept_claim {
+		let owner = funded::<T>(account("owner", 0, 0));
+		let caller = funded::<T>(whitelisted_caller());
+		let claim = claim_of::<T>(0);
+		create::<T>(&owner, claim);
+		PoeModule::<T>::offer_claim(
+			RawOrigin::Signed(owner).into(),
+			claim,
+			caller.clone(),
+			None,
+		)?;
+	}: _(RawOrigin::Signed(caller.clone()), claim)
+	verify {
+		assert_eq!(Proofs::<T>::get(&claim).map(|info| info.owner), Some(caller));
+	}
+
+	reject_claim {
+		let owner = funded::<T>(account("owner", 0, 0));
+		let caller: T::AccountId = whitelisted_caller();
+		let claim = claim_of::<T>(0);
+		create::<T>(&owner, claim);
+		PoeModule::<T>::offer_claim(
+			RawOrigin::Signed(owner).into(),
+			claim,
+			caller.clone(),
+			None,
+		)?;
+	}: _(RawOrigin::Signed(caller), claim)
+	verify {
+		assert!(!PendingTransfers::<T>::contains_key(&claim));
+	}
+
+	cancel_offer {
+		let caller = funded::<T>(whitelisted_caller());
+		let dest: T::AccountId = account("dest", 0, 0);
+		let claim = claim_of::<T>(0);
+		create::<T>(&caller, claim);
+		PoeModule::<T>::offer_claim(RawOrigin::Signed(caller.clone()).into(), claim, dest, None)?;
+	}: _(RawOrigin::Signed(caller), claim)
+	verify {
+		assert!(!PendingTransfers::<T>::contains_key(&claim));
+	}
+
+	set_claim_metadata {
+		let d in 0 .. T::MaxDescriptionLength::get();
+		let t in 0 .. T::MaxContentTypeLength::get();
+		let u in 0 .. T::MaxUriLength::get();
+
+		let caller = funded::<T>(whitelisted_caller());
+		let claim = claim_of::<T>(0);
+		create::<T>(&caller, claim);
+		let description: DescriptionOf<T> = vec![0u8; d as usize].try_into().unwrap();
+		let content_type: ContentTypeOf<T> = vec![0u8; t as usize].try_into().unwrap();
+		let uri: UriOf<T> = vec![0u8; u as usize].try_into().unwrap();
+	}: _(RawOrigin::Signed(caller), claim, description, content_type, Some(uri))
+	verify {
+		assert_eq!(Proofs::<T>::get(&claim).map(|info| info.description.len()), Some(d as usize));
+	}
+
+	impl_benchmark_test_suite!(PoeModule, crate::mock::new_test_ext(), crate::mock::Test);
+}
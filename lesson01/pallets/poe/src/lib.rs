@@ -8,37 +8,379 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod migrations;
+pub mod weights;
+
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::{dispatch::DispatchResult, pallet_prelude::*};
+	pub use crate::weights::WeightInfo;
+	use frame_support::{
+		dispatch::{DispatchResult, DispatchResultWithPostInfo},
+		pallet_prelude::*,
+		traits::{Currency, ReservableCurrency, StorageVersion},
+	};
 	use frame_system::pallet_prelude::*;
+	use scale_info::TypeInfo;
+	use sp_runtime::traits::{Hash, Saturating};
 	use sp_std::vec::Vec;
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub type DescriptionOf<T> = BoundedVec<u8, <T as Config>::MaxDescriptionLength>;
+	pub type ContentTypeOf<T> = BoundedVec<u8, <T as Config>::MaxContentTypeLength>;
+	pub type UriOf<T> = BoundedVec<u8, <T as Config>::MaxUriLength>;
+
+	/// Everything recorded about a claim.
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct ClaimInfo<T: Config> {
+		/// The current owner of the claim.
+		pub owner: T::AccountId,
+		/// The amount reserved from the owner for storing the claim.
+		pub deposit: BalanceOf<T>,
+		/// The block the claim was created in.
+		pub created_at: T::BlockNumber,
+		/// The block the claim was last transferred in, or the creation block.
+		pub transferred_at: T::BlockNumber,
+		/// A human readable description of the notarized content.
+		pub description: DescriptionOf<T>,
+		/// The MIME type of the notarized content.
+		pub content_type: ContentTypeOf<T>,
+		/// Where the notarized content can be retrieved from.
+		pub uri: Option<UriOf<T>>,
+	}
+
+	// The records below implement `MaxEncodedLen` by hand, because deriving it would require
+	// `T: MaxEncodedLen`.
+	impl<T: Config> MaxEncodedLen for ClaimInfo<T> {
+		fn max_encoded_len() -> usize {
+			T::AccountId::max_encoded_len()
+				.saturating_add(BalanceOf::<T>::max_encoded_len())
+				.saturating_add(T::BlockNumber::max_encoded_len().saturating_mul(2))
+				.saturating_add(DescriptionOf::<T>::max_encoded_len())
+				.saturating_add(ContentTypeOf::<T>::max_encoded_len())
+				.saturating_add(Option::<UriOf<T>>::max_encoded_len())
+		}
+	}
+
+	/// A transfer of a claim waiting to be accepted by its recipient.
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct PendingTransfer<T: Config> {
+		/// The account the claim is offered to.
+		pub to: T::AccountId,
+		/// The last block in which the offer can be accepted.
+		pub expires_at: Option<T::BlockNumber>,
+	}
+
+	impl<T: Config> MaxEncodedLen for PendingTransfer<T> {
+		fn max_encoded_len() -> usize {
+			T::AccountId::max_encoded_len()
+				.saturating_add(Option::<T::BlockNumber>::max_encoded_len())
+		}
+	}
+
+	/// A claim waiting for its attesters before it is recorded in `Proofs`.
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct PendingClaim<T: Config> {
+		/// The account that proposed the claim and owns it once it is finalized.
+		pub proposer: T::AccountId,
+		/// The amount reserved from the proposer while the claim is pending.
+		pub deposit: BalanceOf<T>,
+		/// The accounts that may attest the claim, in sorted order.
+		pub attesters: BoundedVec<T::AccountId, T::MaxAttesters>,
+		/// The number of attestations needed to finalize the claim.
+		pub threshold: u32,
+		/// The attesters that have attested the claim so far.
+		pub approvals: BoundedVec<T::AccountId, T::MaxAttesters>,
+	}
+
+	impl<T: Config> MaxEncodedLen for PendingClaim<T> {
+		fn max_encoded_len() -> usize {
+			T::AccountId::max_encoded_len()
+				.saturating_add(BalanceOf::<T>::max_encoded_len())
+				.saturating_add(
+					BoundedVec::<T::AccountId, T::MaxAttesters>::max_encoded_len()
+						.saturating_mul(2),
+				)
+				.saturating_add(u32::max_encoded_len())
+		}
+	}
+
+	/// What happened to a claim in a history entry.
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum ClaimAction {
+		Created,
+		Transferred,
+		Revoked,
+		Expired,
+	}
+
+	/// A change of ownership in the history of a claim.
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct HistoryEntry<T: Config> {
+		/// The owner before the change, `None` when the claim was created.
+		pub from: Option<T::AccountId>,
+		/// The owner after the change, `None` when the claim was removed.
+		pub to: Option<T::AccountId>,
+		/// The block the change happened in.
+		pub block: T::BlockNumber,
+		/// What happened to the claim.
+		pub action: ClaimAction,
+	}
+
+	impl<T: Config> MaxEncodedLen for HistoryEntry<T> {
+		fn max_encoded_len() -> usize {
+			Option::<T::AccountId>::max_encoded_len()
+				.saturating_mul(2)
+				.saturating_add(T::BlockNumber::max_encoded_len())
+				.saturating_add(ClaimAction::max_encoded_len())
+		}
+	}
+
+	/// The provenance of a claim as returned by `claim_history`.
+	#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+	pub enum Provenance<T: Config> {
+		/// The ownership changes of a live claim, oldest first.
+		Active(Vec<HistoryEntry<T>>),
+		/// The claim was removed. Only the entry recording its removal is kept.
+		Tombstone(HistoryEntry<T>),
+	}
+
+	/// The actions an operator may take on behalf of a claim owner, as a set of bit flags.
+	#[derive(
+		Clone, Copy, Encode, Decode, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen,
+	)]
+	pub struct Permissions(pub u8);
+
+	impl Permissions {
+		/// Create claims owned by the owner.
+		pub const CREATE: Self = Self(0b001);
+		/// Revoke claims of the owner.
+		pub const REVOKE: Self = Self(0b010);
+		/// Offer claims of the owner to other accounts.
+		pub const TRANSFER: Self = Self(0b100);
+		/// Every action above.
+		pub const ALL: Self = Self(0b111);
+
+		/// Whether every action in `other` is also in `self`.
+		pub fn contains(self, other: Self) -> bool {
+			self.0 & other.0 == other.0
+		}
+
+		/// Whether no action is allowed at all.
+		pub fn is_empty(self) -> bool {
+			self.0 & Self::ALL.0 == 0
+		}
+	}
+
+	impl sp_std::ops::BitOr for Permissions {
+		type Output = Self;
+
+		fn bitor(self, other: Self) -> Self {
+			Self(self.0 | other.0)
+		}
+	}
+
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
+		/// The currency in which claim deposits are reserved.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// The base amount reserved for every claim.
+		#[pallet::constant]
+		type ClaimDepositBase: Get<BalanceOf<Self>>;
+
+		/// The amount reserved per byte of data stored for a claim.
+		#[pallet::constant]
+		type ClaimDepositPerByte: Get<BalanceOf<Self>>;
+
+		/// The maximum length of a claim description.
+		#[pallet::constant]
+		type MaxDescriptionLength: Get<u32>;
+
+		/// The maximum length of a claim content type.
+		#[pallet::constant]
+		type MaxContentTypeLength: Get<u32>;
+
+		/// The maximum length of a claim URI.
+		#[pallet::constant]
+		type MaxUriLength: Get<u32>;
+
+		/// The maximum number of claims a single account can own.
+		#[pallet::constant]
+		type MaxClaimsPerAccount: Get<u32>;
+
+		/// The maximum number of claims that can expire in a single block.
+		#[pallet::constant]
+		type MaxExpiriesPerBlock: Get<u32>;
+
+		/// The maximum number of attesters of a co-signed claim.
 		#[pallet::constant]
-		type MaxClaimLength: Get<u8>;
+		type MaxAttesters: Get<u32>;
+
+		/// The maximum number of history entries kept per claim. The oldest entries are
+		/// dropped first.
+		#[pallet::constant]
+		type MaxHistoryLength: Get<u32>;
+
+		/// The origin that can revoke or transfer any claim regardless of its owner.
+		type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	#[pallet::generate_storage_info]
 	pub struct Pallet<T>(_);
 
+	/// Claims keyed by the digest of the notarized content.
+	#[pallet::storage]
+	#[pallet::getter(fn proofs)]
+	pub type Proofs<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, ClaimInfo<T>>;
+
+	/// The claims owned by each account.
 	#[pallet::storage]
-	#[pallet::getter(fn proofs  )]
-	pub type Proofs<T: Config> =
-		StorageMap<_, Blake2_128Concat, Vec<u8>, (T::AccountId, T::BlockNumber)>;
+	pub type AccountClaims<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::Hash, ()>;
+
+	/// The number of claims owned by each account.
+	#[pallet::storage]
+	#[pallet::getter(fn claim_count)]
+	pub type AccountClaimCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// Outstanding transfer offers keyed by claim.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_transfers)]
+	pub type PendingTransfers<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, PendingTransfer<T>>;
+
+	/// The number of leaves of every claim that notarizes a Merkle root.
+	#[pallet::storage]
+	#[pallet::getter(fn merkle_claims)]
+	pub type MerkleClaims<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, u32>;
+
+	/// The permissions each owner granted to each of its operators.
+	#[pallet::storage]
+	#[pallet::getter(fn operators)]
+	pub type Operators<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		Permissions,
+	>;
+
+	/// The ownership changes of every live claim, oldest first.
+	#[pallet::storage]
+	pub type History<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,
+		BoundedVec<HistoryEntry<T>, T::MaxHistoryLength>,
+		ValueQuery,
+	>;
+
+	/// The entry recording the removal of every claim that no longer exists.
+	#[pallet::storage]
+	pub type Tombstones<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, HistoryEntry<T>>;
+
+	/// Co-signed claims that have not reached their attestation threshold yet.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_claims)]
+	pub type PendingClaims<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, PendingClaim<T>>;
+
+	/// The block at the start of which each expiring claim is removed.
+	#[pallet::storage]
+	#[pallet::getter(fn claim_expiry)]
+	pub type ClaimExpiry<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, T::BlockNumber>;
+
+	/// The claims removed at the start of each block.
+	#[pallet::storage]
+	#[pallet::getter(fn expiry_schedule)]
+	pub type ExpirySchedule<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<T::Hash, T::MaxExpiriesPerBlock>,
+		ValueQuery,
+	>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Claims created at genesis, with their owners. The usual deposit is reserved from
+		/// each owner.
+		pub claims: Vec<(T::Hash, T::AccountId)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { claims: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (claim, owner) in &self.claims {
+				Pallet::<T>::do_create_claim(owner, *claim, claim.encoded_size())
+					.expect("genesis claims must be unique and affordable by their owners");
+			}
+		}
+	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// Event documentation should end with an array that provides descriptive names for event
-		/// parameters. [something, who]
-		ClaimCreated(T::AccountId, Vec<u8>),
-		ClaimRevoked(T::AccountId, Vec<u8>),
+		/// A claim was created. [who, claim]
+		ClaimCreated(T::AccountId, T::Hash),
+		/// A Merkle root over a batch of documents was notarized. [who, root, leaf_count]
+		MerkleClaimCreated(T::AccountId, T::Hash, u32),
+		/// A claim was revoked. [who, claim]
+		ClaimRevoked(T::AccountId, T::Hash),
+		/// The metadata of a claim was updated. [who, claim]
+		ClaimMetadataSet(T::AccountId, T::Hash),
+		/// A claim was offered to another account. [from, to, claim]
+		ClaimTransferOffered(T::AccountId, T::AccountId, T::Hash),
+		/// A claim changed owner. [from, to, claim]
+		ClaimTransferred(T::AccountId, T::AccountId, T::Hash),
+		/// A transfer offer was cancelled by the owner or rejected by the recipient.
+		/// [from, to, claim]
+		ClaimTransferCancelled(T::AccountId, T::AccountId, T::Hash),
+		/// A co-signed claim was proposed. [proposer, claim, threshold]
+		ClaimProposed(T::AccountId, T::Hash, u32),
+		/// An attester attested a co-signed claim. [attester, claim, approvals]
+		ClaimAttested(T::AccountId, T::Hash, u32),
+		/// A co-signed claim was withdrawn before reaching its threshold. [proposer, claim]
+		ClaimProposalCancelled(T::AccountId, T::Hash),
+		/// An owner approved an operator. [owner, operator, permissions]
+		OperatorApproved(T::AccountId, T::AccountId, Permissions),
+		/// An owner removed an operator. [owner, operator]
+		OperatorRemoved(T::AccountId, T::AccountId),
+		/// An operator acted on a claim on behalf of its owner. Follows the event of the action
+		/// itself. [operator, owner, claim]
+		OperatorActed(T::AccountId, T::AccountId, T::Hash),
+		/// The expiry of a claim was extended. [who, claim, expires_at]
+		ClaimRenewed(T::AccountId, T::Hash, T::BlockNumber),
+		/// A claim reached its expiry block and was removed. [owner, claim]
+		ClaimExpired(T::AccountId, T::Hash),
 	}
 
 	#[pallet::error]
@@ -46,64 +388,788 @@ pub mod pallet {
 		ProofAlreadyExist,
 		ClaimNotExist,
 		NotClaimOwner,
-		ClaimOverflow,
+		NotEnoughBalance,
+		TooManyClaims,
+		TransferAlreadyOffered,
+		TransferToSelf,
+		InvalidExpiry,
+		NoPendingTransfer,
+		NotTransferRecipient,
+		TransferOfferExpired,
+		EmptyMerkleClaim,
+		ClaimNotExpiring,
+		TooManyExpiries,
+		ClaimAlreadyProposed,
+		NoPendingClaim,
+		NotProposer,
+		NotAttester,
+		AlreadyAttested,
+		DuplicateAttester,
+		TooManyAttesters,
+		InvalidThreshold,
+		NotOperator,
+		OperatorIsOwner,
+		EmptyPermissions,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let expired = ExpirySchedule::<T>::take(now);
+			for claim in expired.iter() {
+				ClaimExpiry::<T>::remove(claim);
+				if let Some(info) = Proofs::<T>::get(claim) {
+					Self::remove_claim(claim, &info, ClaimAction::Expired);
+					Self::deposit_event(Event::ClaimExpired(info.owner, *claim));
+				}
+			}
+
+			T::WeightInfo::on_initialize(expired.len() as u32)
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::v1::migrate::<T>()
+				.saturating_add(crate::migrations::v2::migrate::<T>())
+				.saturating_add(crate::migrations::v3::migrate::<T>())
+				.saturating_add(crate::migrations::v4::migrate::<T>())
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(0)]
-		pub fn create_claim(origin: OriginFor<T>, claim: Vec<u8>) -> DispatchResult {
-			ensure!((claim.len() as u8) < T::MaxClaimLength::get(), Error::<T>::ClaimOverflow);
+		// the call index is part of the transaction encoding, so existing calls keep their place
+		// and new ones are appended
+		#[pallet::weight(T::WeightInfo::create_claim())]
+		pub fn create_claim(origin: OriginFor<T>, claim: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			Self::do_create_claim(&sender, claim, claim.encoded_size())?;
+
+			Self::deposit_event(Event::ClaimCreated(sender, claim));
+
+			Ok(())
+		}
 
+		#[pallet::weight(T::WeightInfo::revoke_claim())]
+		pub fn revoke_claim(origin: OriginFor<T>, claim: T::Hash) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 
-			ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+			Self::do_revoke_claim(&sender, claim)
+		}
 
-			let current_block = <frame_system::Pallet<T>>::block_number();
+		/// Offer a claim to `dest`. Ownership only changes once `dest` accepts the offer, at or
+		/// before `expires_at` if given.
+		#[pallet::weight(T::WeightInfo::offer_claim())]
+		pub fn offer_claim(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+			dest: T::AccountId,
+			expires_at: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
 
-			Proofs::<T>::insert(&claim, (&sender, current_block));
+			Self::do_offer_claim(&sender, claim, dest, expires_at)
+		}
+
+		/// Notarize a batch of `leaf_count` documents with the root of a Merkle tree built over
+		/// their digests, see `hash_leaf` and `hash_pair`. Inclusion of a single document can then
+		/// be checked with `verify_inclusion`.
+		#[pallet::weight(T::WeightInfo::create_merkle_claim())]
+		pub fn create_merkle_claim(
+			origin: OriginFor<T>,
+			root: T::Hash,
+			leaf_count: u32,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(leaf_count > 0, Error::<T>::EmptyMerkleClaim);
+
+			Self::do_create_claim(&sender, root, root.encoded_size() + leaf_count.encoded_size())?;
+			MerkleClaims::<T>::insert(&root, leaf_count);
+
+			Self::deposit_event(Event::MerkleClaimCreated(sender, root, leaf_count));
+
+			Ok(())
+		}
+
+		/// Create a claim that is removed automatically at the start of block `expires_at`,
+		/// unless it is renewed before then.
+		#[pallet::weight(T::WeightInfo::create_expiring_claim())]
+		pub fn create_expiring_claim(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+			expires_at: T::BlockNumber,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			Self::ensure_can_schedule(expires_at)?;
+
+			Self::do_create_claim(
+				&sender,
+				claim,
+				claim.encoded_size() + expires_at.encoded_size(),
+			)?;
+			Self::schedule_expiry(&claim, expires_at)?;
 
 			Self::deposit_event(Event::ClaimCreated(sender, claim));
 
 			Ok(())
 		}
 
-		#[pallet::weight(0)]
-		pub fn revoke_claim(origin: OriginFor<T>, claim: Vec<u8>) -> DispatchResult {
-			ensure!((claim.len() as u8) < T::MaxClaimLength::get(), Error::<T>::ClaimOverflow);
+		/// Move the expiry of an expiring claim to the later block `expires_at`.
+		#[pallet::weight(T::WeightInfo::renew_claim())]
+		pub fn renew_claim(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+			expires_at: T::BlockNumber,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(sender == info.owner, Error::<T>::NotClaimOwner);
+
+			let current = ClaimExpiry::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExpiring)?;
+			ensure!(expires_at > current, Error::<T>::InvalidExpiry);
+			Self::ensure_can_schedule(expires_at)?;
+
+			Self::unschedule_expiry(&claim);
+			Self::schedule_expiry(&claim, expires_at)?;
+
+			Self::deposit_event(Event::ClaimRenewed(sender, claim, expires_at));
+
+			Ok(())
+		}
+
+		/// Propose a claim that is only recorded once `threshold` of `attesters` have attested
+		/// it. The sender owns the claim once it is recorded.
+		#[pallet::weight(T::WeightInfo::propose_claim(attesters.len() as u32))]
+		pub fn propose_claim(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+			mut attesters: Vec<T::AccountId>,
+			threshold: u32,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+			ensure!(!PendingClaims::<T>::contains_key(&claim), Error::<T>::ClaimAlreadyProposed);
+
+			let count = attesters.len();
+			attesters.sort();
+			attesters.dedup();
+			ensure!(attesters.len() == count, Error::<T>::DuplicateAttester);
+			ensure!(threshold > 0 && threshold as usize <= count, Error::<T>::InvalidThreshold);
+			let attesters: BoundedVec<_, T::MaxAttesters> =
+				attesters.try_into().map_err(|_| Error::<T>::TooManyAttesters)?;
+
+			let deposit = Self::claim_deposit(claim.encoded_size() + attesters.encoded_size());
+			ensure!(T::Currency::can_reserve(&sender, deposit), Error::<T>::NotEnoughBalance);
+			T::Currency::reserve(&sender, deposit)?;
+
+			PendingClaims::<T>::insert(
+				&claim,
+				PendingClaim {
+					proposer: sender.clone(),
+					deposit,
+					attesters,
+					threshold,
+					approvals: Default::default(),
+				},
+			);
+
+			Self::deposit_event(Event::ClaimProposed(sender, claim, threshold));
+
+			Ok(())
+		}
+
+		/// Attest a co-signed claim. The attestation that reaches the threshold records the
+		/// claim.
+		#[pallet::weight(T::WeightInfo::attest_claim(T::MaxAttesters::get()))]
+		pub fn attest_claim(origin: OriginFor<T>, claim: T::Hash) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let mut pending = PendingClaims::<T>::get(&claim).ok_or(Error::<T>::NoPendingClaim)?;
+			let attesters = pending.attesters.len() as u32;
+
+			ensure!(pending.attesters.contains(&sender), Error::<T>::NotAttester);
+			ensure!(!pending.approvals.contains(&sender), Error::<T>::AlreadyAttested);
+
+			pending
+				.approvals
+				.try_push(sender.clone())
+				.map_err(|_| Error::<T>::TooManyAttesters)?;
+			let approvals = pending.approvals.len() as u32;
+
+			if approvals >= pending.threshold {
+				let proposer = pending.proposer.clone();
+				Self::finalize_claim(&claim, pending)?;
+
+				Self::deposit_event(Event::ClaimAttested(sender, claim, approvals));
+				Self::deposit_event(Event::ClaimCreated(proposer, claim));
+			} else {
+				PendingClaims::<T>::insert(&claim, pending);
+
+				Self::deposit_event(Event::ClaimAttested(sender, claim, approvals));
+			}
+
+			Ok(Some(T::WeightInfo::attest_claim(attesters)).into())
+		}
+
+		/// Withdraw a co-signed claim proposed by the sender and release its deposit.
+		#[pallet::weight(T::WeightInfo::cancel_proposal())]
+		pub fn cancel_proposal(origin: OriginFor<T>, claim: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let pending = PendingClaims::<T>::get(&claim).ok_or(Error::<T>::NoPendingClaim)?;
+
+			ensure!(sender == pending.proposer, Error::<T>::NotProposer);
+
+			T::Currency::unreserve(&sender, pending.deposit);
+			PendingClaims::<T>::remove(&claim);
+
+			Self::deposit_event(Event::ClaimProposalCancelled(sender, claim));
+
+			Ok(())
+		}
+
+		/// Revoke every claim owned by the sender.
+		#[pallet::weight(T::WeightInfo::revoke_all_claims(T::MaxClaimsPerAccount::get()))]
+		pub fn revoke_all_claims(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let claims = Self::claims_of(&sender);
+			for claim in &claims {
+				if let Some(info) = Proofs::<T>::get(claim) {
+					Self::remove_claim(claim, &info, ClaimAction::Revoked);
+					Self::deposit_event(Event::ClaimRevoked(sender.clone(), *claim));
+				}
+			}
+
+			Ok(Some(T::WeightInfo::revoke_all_claims(claims.len() as u32)).into())
+		}
+
+		/// Accept a claim offered to the sender. The claim deposit moves to the sender.
+		#[pallet::weight(T::WeightInfo::accept_claim())]
+		pub fn accept_claim(origin: OriginFor<T>, claim: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let pending =
+				PendingTransfers::<T>::get(&claim).ok_or(Error::<T>::NoPendingTransfer)?;
+
+			ensure!(sender == pending.to, Error::<T>::NotTransferRecipient);
+			if let Some(expires_at) = pending.expires_at {
+				ensure!(
+					<frame_system::Pallet<T>>::block_number() <= expires_at,
+					Error::<T>::TransferOfferExpired
+				);
+			}
+
+			let info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			Self::do_transfer(&claim, info, sender)
+		}
+
+		/// Turn down a claim offered to the sender.
+		#[pallet::weight(T::WeightInfo::reject_claim())]
+		pub fn reject_claim(origin: OriginFor<T>, claim: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let pending =
+				PendingTransfers::<T>::get(&claim).ok_or(Error::<T>::NoPendingTransfer)?;
+
+			ensure!(sender == pending.to, Error::<T>::NotTransferRecipient);
+
+			let info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			PendingTransfers::<T>::remove(&claim);
+
+			Self::deposit_event(Event::ClaimTransferCancelled(info.owner, sender, claim));
+
+			Ok(())
+		}
+
+		/// Withdraw an offer made by the sender.
+		#[pallet::weight(T::WeightInfo::cancel_offer())]
+		pub fn cancel_offer(origin: OriginFor<T>, claim: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(sender == info.owner, Error::<T>::NotClaimOwner);
+
+			let pending =
+				PendingTransfers::<T>::take(&claim).ok_or(Error::<T>::NoPendingTransfer)?;
 
+			Self::deposit_event(Event::ClaimTransferCancelled(sender, pending.to, claim));
+
+			Ok(())
+		}
+
+		/// Describe the content behind a claim. The deposit is adjusted to the new size of the
+		/// claim record. Metadata longer than the configured limits fails to decode.
+		#[pallet::weight(T::WeightInfo::set_claim_metadata(
+			description.len() as u32,
+			content_type.len() as u32,
+			uri.as_ref().map_or(0, |uri| uri.len() as u32),
+		))]
+		pub fn set_claim_metadata(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+			description: DescriptionOf<T>,
+			content_type: ContentTypeOf<T>,
+			uri: Option<UriOf<T>>,
+		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 
-			let (owner, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+			let mut info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
 
-			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(sender == info.owner, Error::<T>::NotClaimOwner);
 
-			Proofs::<T>::remove(&claim);
+			let metadata_len =
+				description.len() + content_type.len() + uri.as_ref().map_or(0, |uri| uri.len());
 
-			Self::deposit_event(Event::ClaimRevoked(sender, claim));
+			info.description = description;
+			info.content_type = content_type;
+			info.uri = uri;
+
+			let deposit = Self::claim_deposit(Self::claim_len(&claim) + metadata_len);
+			if deposit > info.deposit {
+				let extra = deposit.saturating_sub(info.deposit);
+				ensure!(T::Currency::can_reserve(&sender, extra), Error::<T>::NotEnoughBalance);
+				T::Currency::reserve(&sender, extra)?;
+			} else {
+				T::Currency::unreserve(&sender, info.deposit.saturating_sub(deposit));
+			}
+			info.deposit = deposit;
+
+			Proofs::<T>::insert(&claim, info);
+
+			Self::deposit_event(Event::ClaimMetadataSet(sender, claim));
 
 			Ok(())
 		}
 
-		#[pallet::weight(0)]
-		pub fn transfer_claim(
+		/// Revoke a claim regardless of its owner. The deposit is returned to the owner.
+		#[pallet::weight(T::WeightInfo::revoke_claim())]
+		pub fn force_revoke_claim(origin: OriginFor<T>, claim: T::Hash) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			let info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			Self::remove_claim(&claim, &info, ClaimAction::Revoked);
+
+			Self::deposit_event(Event::ClaimRevoked(info.owner, claim));
+
+			Ok(())
+		}
+
+		/// Move a claim to `dest` without an offer or the consent of either party. The deposit
+		/// moves to `dest`.
+		#[pallet::weight(T::WeightInfo::accept_claim())]
+		pub fn force_transfer_claim(
 			origin: OriginFor<T>,
-			claim: Vec<u8>,
+			claim: T::Hash,
 			dest: T::AccountId,
 		) -> DispatchResult {
-			ensure!((claim.len() as u8) < T::MaxClaimLength::get(), Error::<T>::ClaimOverflow);
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			let info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			Self::do_transfer(&claim, info, dest)
+		}
+
+		/// Allow `operator` to take the actions in `permissions` on behalf of the sender,
+		/// replacing any permissions granted before.
+		#[pallet::weight(T::WeightInfo::approve_operator())]
+		pub fn approve_operator(
+			origin: OriginFor<T>,
+			operator: T::AccountId,
+			permissions: Permissions,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(operator != sender, Error::<T>::OperatorIsOwner);
+			ensure!(!permissions.is_empty(), Error::<T>::EmptyPermissions);
 
+			Operators::<T>::insert(&sender, &operator, permissions);
+
+			Self::deposit_event(Event::OperatorApproved(sender, operator, permissions));
+
+			Ok(())
+		}
+
+		/// Withdraw every permission granted to `operator` by the sender.
+		#[pallet::weight(T::WeightInfo::remove_operator())]
+		pub fn remove_operator(origin: OriginFor<T>, operator: T::AccountId) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 
-			let (owner, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+			Operators::<T>::take(&sender, &operator).ok_or(Error::<T>::NotOperator)?;
+
+			Self::deposit_event(Event::OperatorRemoved(sender, operator));
+
+			Ok(())
+		}
+
+		/// Create a claim owned by `owner`, who pays its deposit. Requires the `CREATE`
+		/// permission.
+		#[pallet::weight(T::WeightInfo::create_claim().saturating_add(T::DbWeight::get().reads(1)))]
+		pub fn operator_create_claim(
+			origin: OriginFor<T>,
+			owner: T::AccountId,
+			claim: T::Hash,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
 
-			ensure!(sender == owner, Error::<T>::NotClaimOwner);
+			Self::ensure_operator(&owner, &sender, Permissions::CREATE)?;
 
-			let block_number = <frame_system::Pallet<T>>::block_number();
+			Self::do_create_claim(&owner, claim, claim.encoded_size())?;
 
-			Proofs::<T>::insert(&claim, (&dest, block_number));
+			Self::deposit_event(Event::ClaimCreated(owner.clone(), claim));
+			Self::deposit_event(Event::OperatorActed(sender, owner, claim));
 
 			Ok(())
 		}
+
+		/// Revoke a claim on behalf of its owner. Requires the `REVOKE` permission.
+		#[pallet::weight(T::WeightInfo::revoke_claim().saturating_add(T::DbWeight::get().reads(1)))]
+		pub fn operator_revoke_claim(origin: OriginFor<T>, claim: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let owner = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?.owner;
+			Self::ensure_operator(&owner, &sender, Permissions::REVOKE)?;
+
+			Self::do_revoke_claim(&owner, claim)?;
+
+			Self::deposit_event(Event::OperatorActed(sender, owner, claim));
+
+			Ok(())
+		}
+
+		/// Offer a claim to `dest` on behalf of its owner. Requires the `TRANSFER` permission.
+		#[pallet::weight(T::WeightInfo::offer_claim().saturating_add(T::DbWeight::get().reads(1)))]
+		pub fn operator_offer_claim(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+			dest: T::AccountId,
+			expires_at: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let owner = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?.owner;
+			Self::ensure_operator(&owner, &sender, Permissions::TRANSFER)?;
+
+			Self::do_offer_claim(&owner, claim, dest, expires_at)?;
+
+			Self::deposit_event(Event::OperatorActed(sender, owner, claim));
+
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The deposit required to store `bytes` bytes of claim data. It also covers the
+		/// longest history the claim can have.
+		pub fn claim_deposit(bytes: usize) -> BalanceOf<T> {
+			let history_len = BoundedVec::<HistoryEntry<T>, T::MaxHistoryLength>::max_encoded_len();
+			let bytes = bytes.saturating_add(history_len);
+			T::ClaimDepositBase::get()
+				.saturating_add(T::ClaimDepositPerByte::get().saturating_mul((bytes as u32).into()))
+		}
+
+		/// The number of bytes stored for `claim`, excluding its metadata.
+		fn claim_len(claim: &T::Hash) -> usize {
+			claim.encoded_size() +
+				MerkleClaims::<T>::get(claim).map_or(0, |leaf_count| leaf_count.encoded_size()) +
+				ClaimExpiry::<T>::get(claim).map_or(0, |expires_at| expires_at.encoded_size())
+		}
+
+		/// Check that the document digest `leaf` is part of the batch notarized by the Merkle
+		/// claim `root`.
+		///
+		/// `proof` lists the sibling hashes from the leaf up to the root. Pairs are hashed in
+		/// sorted order, see `hash_pair`, so the proof carries no position information. Its length
+		/// must match the depth of a tree over the notarized number of leaves, see `merkle_depth`.
+		pub fn verify_inclusion(root: T::Hash, leaf: T::Hash, proof: Vec<T::Hash>) -> bool {
+			let leaf_count = match MerkleClaims::<T>::get(&root) {
+				Some(leaf_count) => leaf_count,
+				None => return false,
+			};
+			if proof.len() != Self::merkle_depth(leaf_count) {
+				return false
+			}
+
+			let node = Self::hash_leaf(leaf);
+			proof.into_iter().fold(node, |node, sibling| Self::hash_pair(node, sibling)) == root
+		}
+
+		/// The number of levels above the leaves in a tree over `leaf_count` leaves. A node
+		/// without a sibling on its level is paired with itself, so every leaf sits at this depth.
+		pub fn merkle_depth(leaf_count: u32) -> usize {
+			(u32::BITS - leaf_count.saturating_sub(1).leading_zeros()) as usize
+		}
+
+		/// Hash a document digest into a Merkle tree leaf. Leaves and inner nodes are hashed
+		/// with different prefixes, so that an inner node can never pass for a leaf.
+		pub fn hash_leaf(leaf: T::Hash) -> T::Hash {
+			T::Hashing::hash(&[&[0u8][..], leaf.as_ref()].concat())
+		}
+
+		/// Hash two Merkle tree nodes into their parent node.
+		pub fn hash_pair(a: T::Hash, b: T::Hash) -> T::Hash {
+			let (first, second) = if a <= b { (a, b) } else { (b, a) };
+			T::Hashing::hash(&[&[1u8][..], first.as_ref(), second.as_ref()].concat())
+		}
+
+		/// Reserve the deposit for `bytes` bytes of claim data and record `claim` as owned by
+		/// `who`.
+		fn do_create_claim(who: &T::AccountId, claim: T::Hash, bytes: usize) -> DispatchResult {
+			ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+			ensure!(!PendingClaims::<T>::contains_key(&claim), Error::<T>::ClaimAlreadyProposed);
+
+			let deposit = Self::claim_deposit(bytes);
+			ensure!(T::Currency::can_reserve(who, deposit), Error::<T>::NotEnoughBalance);
+
+			Self::add_account_claim(who, &claim)?;
+			T::Currency::reserve(who, deposit)?;
+
+			Self::insert_claim(who, claim, deposit);
+
+			Ok(())
+		}
+
+		/// Record a co-signed claim that reached its threshold. The part of the proposal deposit
+		/// that is not needed for the claim itself is released.
+		fn finalize_claim(claim: &T::Hash, pending: PendingClaim<T>) -> DispatchResult {
+			ensure!(!Proofs::<T>::contains_key(claim), Error::<T>::ProofAlreadyExist);
+
+			Self::add_account_claim(&pending.proposer, claim)?;
+
+			let deposit = Self::claim_deposit(claim.encoded_size()).min(pending.deposit);
+			T::Currency::unreserve(&pending.proposer, pending.deposit.saturating_sub(deposit));
+
+			PendingClaims::<T>::remove(claim);
+			Self::insert_claim(&pending.proposer, *claim, deposit);
+
+			Ok(())
+		}
+
+		fn insert_claim(who: &T::AccountId, claim: T::Hash, deposit: BalanceOf<T>) {
+			let current_block = <frame_system::Pallet<T>>::block_number();
+
+			Proofs::<T>::insert(
+				&claim,
+				ClaimInfo {
+					owner: who.clone(),
+					deposit,
+					created_at: current_block,
+					transferred_at: current_block,
+					description: Default::default(),
+					content_type: Default::default(),
+					uri: None,
+				},
+			);
+
+			Tombstones::<T>::remove(&claim);
+			History::<T>::remove(&claim);
+			Self::record_history(&claim, None, Some(who.clone()), ClaimAction::Created);
+		}
+
+		/// Revoke `claim`, which must be owned by `who`.
+		fn do_revoke_claim(who: &T::AccountId, claim: T::Hash) -> DispatchResult {
+			let info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(&info.owner == who, Error::<T>::NotClaimOwner);
+
+			Self::remove_claim(&claim, &info, ClaimAction::Revoked);
+
+			Self::deposit_event(Event::ClaimRevoked(who.clone(), claim));
+
+			Ok(())
+		}
+
+		/// Offer `claim`, which must be owned by `who`, to `dest`.
+		fn do_offer_claim(
+			who: &T::AccountId,
+			claim: T::Hash,
+			dest: T::AccountId,
+			expires_at: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			let info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(&info.owner == who, Error::<T>::NotClaimOwner);
+			ensure!(&dest != who, Error::<T>::TransferToSelf);
+			ensure!(
+				!PendingTransfers::<T>::contains_key(&claim),
+				Error::<T>::TransferAlreadyOffered
+			);
+			if let Some(expires_at) = expires_at {
+				ensure!(
+					expires_at >= <frame_system::Pallet<T>>::block_number(),
+					Error::<T>::InvalidExpiry
+				);
+			}
+
+			PendingTransfers::<T>::insert(&claim, PendingTransfer { to: dest.clone(), expires_at });
+
+			Self::deposit_event(Event::ClaimTransferOffered(who.clone(), dest, claim));
+
+			Ok(())
+		}
+
+		/// Check that `operator` may take every action in `permissions` on behalf of `owner`.
+		fn ensure_operator(
+			owner: &T::AccountId,
+			operator: &T::AccountId,
+			permissions: Permissions,
+		) -> DispatchResult {
+			let granted = Operators::<T>::get(owner, operator).unwrap_or_default();
+			ensure!(granted.contains(permissions), Error::<T>::NotOperator);
+
+			Ok(())
+		}
+
+		/// All claims owned by `who`.
+		pub fn claims_of(who: &T::AccountId) -> Vec<T::Hash> {
+			AccountClaims::<T>::iter_prefix(who).map(|(claim, _)| claim).collect()
+		}
+
+		/// Remove a claim, its index entry, any pending offer and its expiry, and release its
+		/// deposit in full. Its history is replaced by a tombstone recording `action`.
+		fn remove_claim(claim: &T::Hash, info: &ClaimInfo<T>, action: ClaimAction) {
+			T::Currency::unreserve(&info.owner, info.deposit);
+			Self::remove_account_claim(&info.owner, claim);
+			Self::unschedule_expiry(claim);
+			PendingTransfers::<T>::remove(claim);
+			MerkleClaims::<T>::remove(claim);
+			Proofs::<T>::remove(claim);
+
+			History::<T>::remove(claim);
+			Tombstones::<T>::insert(
+				claim,
+				HistoryEntry {
+					from: Some(info.owner.clone()),
+					to: None,
+					block: <frame_system::Pallet<T>>::block_number(),
+					action,
+				},
+			);
+		}
+
+		/// The ownership changes of `claim`, or its tombstone if it was removed.
+		pub fn claim_history(claim: &T::Hash) -> Option<Provenance<T>> {
+			if let Some(tombstone) = Tombstones::<T>::get(claim) {
+				return Some(Provenance::Tombstone(tombstone))
+			}
+
+			Proofs::<T>::contains_key(claim)
+				.then(|| Provenance::Active(History::<T>::get(claim).into_inner()))
+		}
+
+		/// Append an entry to the history of `claim`, dropping the oldest entry when it is full.
+		fn record_history(
+			claim: &T::Hash,
+			from: Option<T::AccountId>,
+			to: Option<T::AccountId>,
+			action: ClaimAction,
+		) {
+			let entry =
+				HistoryEntry { from, to, block: <frame_system::Pallet<T>>::block_number(), action };
+			History::<T>::mutate(claim, |history| {
+				if !history.is_empty() && history.len() >= T::MaxHistoryLength::get() as usize {
+					history.remove(0);
+				}
+				// Only fails when `MaxHistoryLength` is zero, in which case nothing is kept.
+				let _ = history.try_push(entry);
+			});
+		}
+
+		/// Check that a claim can be scheduled to expire at the start of block `expires_at`.
+		fn ensure_can_schedule(expires_at: T::BlockNumber) -> DispatchResult {
+			ensure!(
+				expires_at > <frame_system::Pallet<T>>::block_number(),
+				Error::<T>::InvalidExpiry
+			);
+			ensure!(
+				ExpirySchedule::<T>::decode_len(expires_at).unwrap_or(0) <
+					T::MaxExpiriesPerBlock::get() as usize,
+				Error::<T>::TooManyExpiries
+			);
+
+			Ok(())
+		}
+
+		fn schedule_expiry(claim: &T::Hash, expires_at: T::BlockNumber) -> DispatchResult {
+			ExpirySchedule::<T>::try_mutate(expires_at, |claims| {
+				claims.try_push(*claim).map_err(|_| Error::<T>::TooManyExpiries)
+			})?;
+			ClaimExpiry::<T>::insert(claim, expires_at);
+
+			Ok(())
+		}
+
+		fn unschedule_expiry(claim: &T::Hash) {
+			if let Some(expires_at) = ClaimExpiry::<T>::take(claim) {
+				ExpirySchedule::<T>::mutate_exists(expires_at, |claims| {
+					if let Some(list) = claims {
+						list.retain(|c| c != claim);
+						if list.is_empty() {
+							*claims = None;
+						}
+					}
+				});
+			}
+		}
+
+		/// Move a claim and its deposit to `dest`, dropping any pending offer.
+		fn do_transfer(
+			claim: &T::Hash,
+			mut info: ClaimInfo<T>,
+			dest: T::AccountId,
+		) -> DispatchResult {
+			ensure!(T::Currency::can_reserve(&dest, info.deposit), Error::<T>::NotEnoughBalance);
+
+			let from = info.owner.clone();
+			if dest != from {
+				Self::add_account_claim(&dest, claim)?;
+				Self::remove_account_claim(&from, claim);
+			}
+			T::Currency::reserve(&dest, info.deposit)?;
+			T::Currency::unreserve(&from, info.deposit);
+
+			info.owner = dest.clone();
+			info.transferred_at = <frame_system::Pallet<T>>::block_number();
+
+			PendingTransfers::<T>::remove(claim);
+			Proofs::<T>::insert(claim, info);
+			Self::record_history(
+				claim,
+				Some(from.clone()),
+				Some(dest.clone()),
+				ClaimAction::Transferred,
+			);
+
+			Self::deposit_event(Event::ClaimTransferred(from, dest, *claim));
+
+			Ok(())
+		}
+
+		fn add_account_claim(who: &T::AccountId, claim: &T::Hash) -> DispatchResult {
+			let count = AccountClaimCount::<T>::get(who);
+			ensure!(count < T::MaxClaimsPerAccount::get(), Error::<T>::TooManyClaims);
+
+			AccountClaims::<T>::insert(who, claim, ());
+			AccountClaimCount::<T>::insert(who, count + 1);
+
+			Ok(())
+		}
+
+		fn remove_account_claim(who: &T::AccountId, claim: &T::Hash) {
+			if AccountClaims::<T>::take(who, claim).is_some() {
+				AccountClaimCount::<T>::mutate_exists(who, |count| {
+					*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0)
+				});
+			}
+		}
 	}
 }
@@ -0,0 +1,143 @@
+//! Storage migrations for the poe pallet.
+
+use super::*;
+use codec::Encode;
+use frame_support::{
+	storage::migration::{put_storage_value, storage_key_iter},
+	traits::{Get, GetStorageVersion, PalletInfoAccess, StorageVersion},
+	weights::Weight,
+	Blake2_128Concat, StorageHasher,
+};
+use sp_runtime::traits::{Hash, Zero};
+use sp_std::vec::Vec;
+
+const PROOFS: &[u8] = b"Proofs";
+
+fn pallet_prefix<T: Config>() -> &'static [u8] {
+	<Pallet<T>>::name().as_bytes()
+}
+
+fn put_proof<T: Config, V: Encode>(claim: &T::Hash, value: V) {
+	put_storage_value(
+		pallet_prefix::<T>(),
+		PROOFS,
+		&Blake2_128Concat::hash(&claim.encode()),
+		value,
+	);
+}
+
+pub mod v1 {
+	use super::*;
+
+	/// Re-key `Proofs` from the raw claim bytes to the `T::Hashing` digest of those bytes.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 1 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let old_proofs: Vec<(Vec<u8>, (T::AccountId, T::BlockNumber))> =
+			storage_key_iter::<Vec<u8>, (T::AccountId, T::BlockNumber), Blake2_128Concat>(
+				pallet_prefix::<T>(),
+				PROOFS,
+			)
+			.drain()
+			.collect();
+
+		let count = old_proofs.len() as Weight;
+		for (claim, proof) in old_proofs {
+			put_proof::<T, _>(&T::Hashing::hash(&claim), proof);
+		}
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count + 1, count * 2 + 1)
+	}
+}
+
+pub mod v2 {
+	use super::*;
+
+	/// Add the reserved deposit to every `Proofs` record. Claims created before deposits
+	/// existed have nothing reserved, so they are recorded with a zero deposit.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 2 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let old_proofs: Vec<(T::Hash, (T::AccountId, T::BlockNumber))> =
+			storage_key_iter::<T::Hash, (T::AccountId, T::BlockNumber), Blake2_128Concat>(
+				pallet_prefix::<T>(),
+				PROOFS,
+			)
+			.collect();
+
+		let count = old_proofs.len() as Weight;
+		for (claim, (owner, block)) in old_proofs {
+			put_proof::<T, _>(&claim, (owner, block, BalanceOf::<T>::zero()));
+		}
+
+		StorageVersion::new(2).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count + 1, count + 1)
+	}
+}
+
+pub mod v3 {
+	use super::*;
+
+	/// Replace the `(owner, block, deposit)` tuple in `Proofs` with a `ClaimInfo` record that
+	/// carries empty metadata. The creation block is unknown for claims that have been
+	/// transferred, so the last recorded block is used for both.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 3 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let old_proofs: Vec<(T::Hash, (T::AccountId, T::BlockNumber, BalanceOf<T>))> =
+			storage_key_iter::<
+				T::Hash,
+				(T::AccountId, T::BlockNumber, BalanceOf<T>),
+				Blake2_128Concat,
+			>(pallet_prefix::<T>(), PROOFS)
+			.collect();
+
+		let count = old_proofs.len() as Weight;
+		for (claim, (owner, block, deposit)) in old_proofs {
+			let info =
+				(owner, deposit, block, block, Vec::<u8>::new(), Vec::<u8>::new(), None::<Vec<u8>>);
+			put_proof::<T, _>(&claim, info);
+		}
+
+		StorageVersion::new(3).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count + 1, count + 1)
+	}
+}
+
+pub mod v4 {
+	use super::*;
+
+	/// Build the `AccountClaims` index and `AccountClaimCount` from the existing `Proofs`.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 4 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let owners: Vec<(T::Hash, T::AccountId)> = storage_key_iter::<
+			T::Hash,
+			T::AccountId,
+			Blake2_128Concat,
+		>(pallet_prefix::<T>(), PROOFS)
+		.collect();
+
+		let count = owners.len() as Weight;
+		for (claim, owner) in owners {
+			AccountClaims::<T>::insert(&owner, claim, ());
+			AccountClaimCount::<T>::mutate(&owner, |count| *count = count.saturating_add(1));
+		}
+
+		StorageVersion::new(4).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count * 2 + 1, count * 2 + 1)
+	}
+}
@@ -1,4 +1,4 @@
-crate as pallet_poe;
+use crate as pallet_poe;
 use frame_support::parameter_types;
 use frame_system as system;
 use sp_core::H256;
@@ -18,14 +18,14 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		PoeModule: pallet_poe::{Pallet, Call, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		PoeModule: pallet_poe::{Pallet, Call, Config<T>, Storage, Event<T>},
 	}
 );
 
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
 	pub const SS58Prefix: u8 = 42;
-	pub const MaxClaimLength: u8 = 3;
 }
 
 impl system::Config for Test {
@@ -46,7 +46,7 @@ impl system::Config for Test {
 	type BlockHashCount = BlockHashCount;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
@@ -54,12 +54,54 @@ impl system::Config for Test {
 	type OnSetCode = ();
 }
 
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+	pub const ClaimDepositBase: u64 = 2;
+	pub const ClaimDepositPerByte: u64 = 1;
+	pub const MaxDescriptionLength: u32 = 16;
+	pub const MaxContentTypeLength: u32 = 8;
+	pub const MaxUriLength: u32 = 16;
+	pub const MaxClaimsPerAccount: u32 = 3;
+	pub const MaxExpiriesPerBlock: u32 = 2;
+	pub const MaxAttesters: u32 = 3;
+	pub const MaxHistoryLength: u32 = 3;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
 impl pallet_poe::Config for Test {
 	type Event = Event;
-	type MaxClaimLength = MaxClaimLength;
+	type Currency = Balances;
+	type ClaimDepositBase = ClaimDepositBase;
+	type ClaimDepositPerByte = ClaimDepositPerByte;
+	type MaxDescriptionLength = MaxDescriptionLength;
+	type MaxContentTypeLength = MaxContentTypeLength;
+	type MaxUriLength = MaxUriLength;
+	type MaxClaimsPerAccount = MaxClaimsPerAccount;
+	type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+	type MaxAttesters = MaxAttesters;
+	type MaxHistoryLength = MaxHistoryLength;
+	type ForceOrigin = system::EnsureRoot<u64>;
+	type WeightInfo = ();
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 1_000), (2, 1_000), (3, 1_000), (4, 10)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	t.into()
 }
@@ -1,15 +1,55 @@
-use crate::{mock::*, Error, Proofs};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	mock::*, AccountClaims, ClaimAction, Error, HistoryEntry, Permissions, Proofs, Provenance,
+	WeightInfo,
+};
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::migration::put_storage_value,
+	traits::{
+		GenesisBuild, Get, GetStorageVersion, OnInitialize, OnRuntimeUpgrade, StorageInfoTrait,
+		StorageVersion,
+	},
+	Blake2_128Concat, BoundedVec, StorageHasher,
+};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+fn claim_of(content: &[u8]) -> H256 {
+	BlakeTwo256::hash(content)
+}
+
+fn deposit_of(claim: &H256) -> u64 {
+	PoeModule::claim_deposit(claim.encoded_size())
+}
+
+fn bounded<S: Get<u32>>(bytes: &[u8]) -> BoundedVec<u8, S> {
+	bytes.to_vec().try_into().unwrap()
+}
+
+fn entry(
+	from: Option<u64>,
+	to: Option<u64>,
+	block: u64,
+	action: ClaimAction,
+) -> HistoryEntry<Test> {
+	HistoryEntry { from, to, block, action }
+}
 
 #[test]
 fn create_claim_works() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
-		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
-		assert_eq!(
-			Proofs::<Test>::get(&claim),
-			Some((1, <frame_system::Pallet<Test>>::block_number()))
-		)
+		let claim = claim_of(&[0, 1]);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim));
+
+		let info = Proofs::<Test>::get(&claim).unwrap();
+		assert_eq!(info.owner, 1);
+		assert_eq!(info.deposit, deposit_of(&claim));
+		assert_eq!(info.created_at, <frame_system::Pallet<Test>>::block_number());
+		assert_eq!(info.transferred_at, info.created_at);
+		assert_eq!(Balances::reserved_balance(1), deposit_of(&claim));
+		assert!(AccountClaims::<Test>::contains_key(1, claim));
+		assert_eq!(PoeModule::claim_count(1), 1);
 	});
 }
 
@@ -17,72 +57,318 @@ fn create_claim_works() {
 #[ignore]
 fn create_claim_falied_with_bad_origin() {
 	assert_noop!(
-		PoeModule::create_claim(Origin::root(), "un".to_string().as_bytes().to_vec()),
+		PoeModule::create_claim(Origin::root(), claim_of("un".as_bytes())),
 		frame_support::error::BadOrigin
 	);
 }
 
 #[test]
-fn create_claim_failed_when_claim_is_too_long() {
+fn genesis_config_creates_claims() {
+	let claims = vec![(claim_of(&[0, 1]), 1), (claim_of(&[2, 3]), 2)];
+
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 1_000), (2, 1_000)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	crate::GenesisConfig::<Test> { claims: claims.clone() }
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+	sp_io::TestExternalities::new(t).execute_with(|| {
+		for (claim, owner) in claims {
+			let info = Proofs::<Test>::get(&claim).unwrap();
+			assert_eq!(info.owner, owner);
+			assert_eq!(info.created_at, 0);
+			assert_eq!(Balances::reserved_balance(owner), deposit_of(&claim));
+			assert_eq!(PoeModule::claims_of(&owner), vec![claim]);
+		}
+	});
+}
+
+#[test]
+fn create_claim_failed_when_claim_already_exist() {
 	new_test_ext().execute_with(|| {
-		let limit_size = MaxClaimLength::get() as usize;
+		let claim = claim_of(&[0, 1]);
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
 
 		assert_noop!(
-			PoeModule::create_claim(
-				Origin::signed(1),
-				vec![0u8; limit_size.checked_add(1).unwrap()]
-			),
-			Error::<Test>::ClaimOverflow
+			PoeModule::create_claim(Origin::signed(1), claim),
+			Error::<Test>::ProofAlreadyExist
 		);
 	})
 }
 
 #[test]
-fn create_claim_failed_when_claim_already_exist() {
+fn create_claim_failed_with_not_enough_balance() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let claim = claim_of(&[0, 1]);
 
 		assert_noop!(
-			PoeModule::create_claim(Origin::signed(1), claim.clone()),
-			Error::<Test>::ProofAlreadyExist
+			PoeModule::create_claim(Origin::signed(4), claim),
+			Error::<Test>::NotEnoughBalance
 		);
 	})
 }
 
 #[test]
-fn revoke_claim_works() {
+fn create_claim_failed_with_too_many_claims() {
+	new_test_ext().execute_with(|| {
+		for i in 0..MaxClaimsPerAccount::get() as u8 {
+			assert_ok!(PoeModule::create_claim(Origin::signed(1), claim_of(&[i])));
+		}
+
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), claim_of(&[u8::MAX])),
+			Error::<Test>::TooManyClaims
+		);
+	})
+}
+
+#[test]
+fn create_merkle_claim_works() {
+	new_test_ext().execute_with(|| {
+		let leaves: Vec<H256> = (0u8..4).map(|i| claim_of(&[i])).collect();
+		let nodes: Vec<H256> = leaves.iter().map(|leaf| PoeModule::hash_leaf(*leaf)).collect();
+		let left = PoeModule::hash_pair(nodes[0], nodes[1]);
+		let right = PoeModule::hash_pair(nodes[2], nodes[3]);
+		let root = PoeModule::hash_pair(left, right);
+
+		assert!(!PoeModule::verify_inclusion(root, leaves[0], vec![nodes[1], right]));
+
+		assert_ok!(PoeModule::create_merkle_claim(Origin::signed(1), root, 4));
+		assert_eq!(Proofs::<Test>::get(&root).unwrap().owner, 1);
+		assert_eq!(PoeModule::merkle_claims(&root), Some(4));
+
+		assert!(PoeModule::verify_inclusion(root, leaves[0], vec![nodes[1], right]));
+		assert!(PoeModule::verify_inclusion(root, leaves[3], vec![nodes[2], left]));
+		assert!(!PoeModule::verify_inclusion(root, leaves[0], vec![nodes[2], right]));
+		assert!(!PoeModule::verify_inclusion(root, claim_of(&[4]), vec![nodes[1], right]));
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), root));
+		assert_eq!(PoeModule::merkle_claims(&root), None);
+		assert!(!PoeModule::verify_inclusion(root, leaves[0], vec![nodes[1], right]));
+	})
+}
+
+#[test]
+fn verify_inclusion_rejects_inner_nodes_as_leaves() {
+	new_test_ext().execute_with(|| {
+		let nodes: Vec<H256> = (0u8..4).map(|i| PoeModule::hash_leaf(claim_of(&[i]))).collect();
+		let left = PoeModule::hash_pair(nodes[0], nodes[1]);
+		let right = PoeModule::hash_pair(nodes[2], nodes[3]);
+		let root = PoeModule::hash_pair(left, right);
+		assert_ok!(PoeModule::create_merkle_claim(Origin::signed(1), root, 4));
+
+		assert!(PoeModule::verify_inclusion(root, claim_of(&[0]), vec![nodes[1], right]));
+		assert!(!PoeModule::verify_inclusion(root, root, vec![]));
+		assert!(!PoeModule::verify_inclusion(root, left, vec![right]));
+		// tree nodes are not document digests, even with a proof of the right length
+		assert!(!PoeModule::verify_inclusion(root, nodes[0], vec![nodes[1], right]));
+	})
+}
+
+#[test]
+fn verify_inclusion_rejects_proofs_of_the_wrong_length() {
+	new_test_ext().execute_with(|| {
+		let nodes: Vec<H256> = (0u8..3).map(|i| PoeModule::hash_leaf(claim_of(&[i]))).collect();
+		let left = PoeModule::hash_pair(nodes[0], nodes[1]);
+		let right = PoeModule::hash_pair(nodes[2], nodes[2]);
+		let root = PoeModule::hash_pair(left, right);
+		assert_ok!(PoeModule::create_merkle_claim(Origin::signed(1), root, 3));
+
+		assert!(PoeModule::verify_inclusion(root, claim_of(&[2]), vec![nodes[2], left]));
+		assert!(!PoeModule::verify_inclusion(root, claim_of(&[2]), vec![left]));
+		assert!(!PoeModule::verify_inclusion(root, claim_of(&[2]), vec![nodes[2], left, root]));
+		assert_eq!((1..=5).map(PoeModule::merkle_depth).collect::<Vec<_>>(), vec![0, 1, 2, 2, 3]);
+	})
+}
+
+#[test]
+fn create_merkle_claim_failed_without_leaves() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::create_merkle_claim(Origin::signed(1), claim_of(&[0]), 0),
+			Error::<Test>::EmptyMerkleClaim
+		);
+	})
+}
+
+#[test]
+fn propose_and_attest_claim_works() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		System::set_block_number(1);
+		let claim = claim_of(&[0, 1]);
+
+		assert_ok!(PoeModule::propose_claim(Origin::signed(1), claim, vec![3, 2], 2));
+
+		let pending = PoeModule::pending_claims(&claim).unwrap();
+		assert_eq!(pending.attesters.clone().into_inner(), vec![2, 3]);
+		assert_eq!(Balances::reserved_balance(1), pending.deposit);
+		assert_eq!(
+			pending.deposit,
+			PoeModule::claim_deposit(claim.encoded_size() + pending.attesters.encoded_size())
+		);
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(2), claim),
+			Error::<Test>::ClaimAlreadyProposed
+		);
 
-		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::attest_claim(Origin::signed(2), claim));
 		assert_eq!(Proofs::<Test>::get(&claim), None);
+		System::assert_last_event(Event::PoeModule(crate::Event::ClaimAttested(2, claim, 1)));
+
+		assert_ok!(PoeModule::attest_claim(Origin::signed(3), claim));
+		assert_eq!(PoeModule::pending_claims(&claim), None);
+		let info = Proofs::<Test>::get(&claim).unwrap();
+		assert_eq!(info.owner, 1);
+		assert_eq!(info.deposit, deposit_of(&claim));
+		assert_eq!(Balances::reserved_balance(1), deposit_of(&claim));
+		assert_eq!(PoeModule::claims_of(&1), vec![claim]);
+		System::assert_has_event(Event::PoeModule(crate::Event::ClaimAttested(3, claim, 2)));
+		System::assert_last_event(Event::PoeModule(crate::Event::ClaimCreated(1, claim)));
 	})
 }
 
 #[test]
-fn revoke_claim_failed_when_claim_is_too_long() {
+fn propose_claim_failed_with_invalid_attesters() {
 	new_test_ext().execute_with(|| {
-		let limit_size = MaxClaimLength::get() as usize;
+		let claim = claim_of(&[0, 1]);
 
 		assert_noop!(
-			PoeModule::revoke_claim(
-				Origin::signed(1),
-				vec![0u8; limit_size.checked_add(1).unwrap()]
-			),
-			Error::<Test>::ClaimOverflow
+			PoeModule::propose_claim(Origin::signed(1), claim, vec![2, 3], 0),
+			Error::<Test>::InvalidThreshold
+		);
+		assert_noop!(
+			PoeModule::propose_claim(Origin::signed(1), claim, vec![2, 3], 3),
+			Error::<Test>::InvalidThreshold
+		);
+		assert_noop!(
+			PoeModule::propose_claim(Origin::signed(1), claim, vec![2, 3, 2], 2),
+			Error::<Test>::DuplicateAttester
+		);
+		assert_noop!(
+			PoeModule::propose_claim(Origin::signed(1), claim, vec![2, 3, 4, 5], 2),
+			Error::<Test>::TooManyAttesters
+		);
+	})
+}
+
+#[test]
+fn propose_claim_failed_when_claim_exists() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+		assert_noop!(
+			PoeModule::propose_claim(Origin::signed(2), claim, vec![3], 1),
+			Error::<Test>::ProofAlreadyExist
+		);
+
+		let proposed = claim_of(&[2, 3]);
+		let _ = PoeModule::propose_claim(Origin::signed(1), proposed, vec![3], 1);
+		assert_noop!(
+			PoeModule::propose_claim(Origin::signed(2), proposed, vec![3], 1),
+			Error::<Test>::ClaimAlreadyProposed
+		);
+	})
+}
+
+#[test]
+fn attest_claim_failed_with_invalid_attester() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		assert_noop!(
+			PoeModule::attest_claim(Origin::signed(2), claim),
+			Error::<Test>::NoPendingClaim
+		);
+
+		let _ = PoeModule::propose_claim(Origin::signed(1), claim, vec![2, 3], 2);
+		let _ = PoeModule::attest_claim(Origin::signed(2), claim);
+
+		assert_noop!(PoeModule::attest_claim(Origin::signed(1), claim), Error::<Test>::NotAttester);
+		assert_noop!(
+			PoeModule::attest_claim(Origin::signed(2), claim),
+			Error::<Test>::AlreadyAttested
+		);
+	})
+}
+
+#[test]
+fn attest_claim_refunds_weight_of_unused_attester_slots() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		assert_ok!(PoeModule::propose_claim(Origin::signed(1), claim, vec![2, 3], 2));
+		let post_info = PoeModule::attest_claim(Origin::signed(2), claim).unwrap();
+		assert_eq!(post_info.actual_weight, Some(<() as WeightInfo>::attest_claim(2)));
+	})
+}
+
+#[test]
+fn cancel_proposal_works() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::propose_claim(Origin::signed(1), claim, vec![2, 3], 2);
+
+		assert_noop!(
+			PoeModule::cancel_proposal(Origin::signed(2), claim),
+			Error::<Test>::NotProposer
+		);
+
+		assert_ok!(PoeModule::cancel_proposal(Origin::signed(1), claim));
+		assert_eq!(PoeModule::pending_claims(&claim), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_noop!(
+			PoeModule::attest_claim(Origin::signed(2), claim),
+			Error::<Test>::NoPendingClaim
 		);
 	})
 }
 
+#[test]
+fn revoke_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+		assert_eq!(Proofs::<Test>::get(&claim), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(!AccountClaims::<Test>::contains_key(1, claim));
+		assert_eq!(PoeModule::claim_count(1), 0);
+	})
+}
+
+#[test]
+fn revoke_all_claims_works() {
+	new_test_ext().execute_with(|| {
+		let claims = vec![claim_of(&[0]), claim_of(&[1]), claim_of(&[2])];
+		for claim in &claims {
+			let _ = PoeModule::create_claim(Origin::signed(1), *claim);
+		}
+		let _ = PoeModule::create_claim(Origin::signed(2), claim_of(&[3]));
+
+		assert_ok!(PoeModule::revoke_all_claims(Origin::signed(1)));
+
+		for claim in &claims {
+			assert_eq!(Proofs::<Test>::get(claim), None);
+		}
+		assert!(PoeModule::claims_of(&1).is_empty());
+		assert_eq!(PoeModule::claim_count(1), 0);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(PoeModule::claims_of(&2), vec![claim_of(&[3])]);
+	})
+}
+
 #[test]
 fn revoke_claim_failed_when_claim_is_not_exist() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
+		let claim = claim_of(&[0, 1]);
 
 		assert_noop!(
-			PoeModule::revoke_claim(Origin::signed(1), claim.clone()),
+			PoeModule::revoke_claim(Origin::signed(1), claim),
 			Error::<Test>::ClaimNotExist
 		);
 	})
@@ -91,70 +377,704 @@ fn revoke_claim_failed_when_claim_is_not_exist() {
 #[test]
 fn revoke_claim_failed_when_origin_is_not_claim_owner() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
+		let claim = claim_of(&[0, 1]);
 
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
 
 		assert_noop!(
-			PoeModule::revoke_claim(Origin::signed(2), claim.clone()),
+			PoeModule::revoke_claim(Origin::signed(2), claim),
 			Error::<Test>::NotClaimOwner
 		);
 	})
 }
 
 #[test]
-fn transfer_claim_works() {
+fn offer_and_accept_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+
+		assert_ok!(PoeModule::offer_claim(Origin::signed(1), claim, 2, None));
+		assert_eq!(Proofs::<Test>::get(&claim).unwrap().owner, 1);
+
+		System::set_block_number(5);
+		assert_ok!(PoeModule::accept_claim(Origin::signed(2), claim));
+
+		let info = Proofs::<Test>::get(&claim).unwrap();
+		assert_eq!(info.owner, 2);
+		assert_eq!(info.created_at, 0);
+		assert_eq!(info.transferred_at, 5);
+		assert_eq!(PoeModule::pending_transfers(&claim), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), deposit_of(&claim));
+		assert!(PoeModule::claims_of(&1).is_empty());
+		assert_eq!(PoeModule::claims_of(&2), vec![claim]);
+		System::assert_last_event(Event::PoeModule(crate::Event::ClaimTransferred(1, 2, claim)));
+	})
+}
+
+#[test]
+fn offer_claim_failed_when_claim_not_exist() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		assert_noop!(
+			PoeModule::offer_claim(Origin::signed(1), claim, 2, None),
+			Error::<Test>::ClaimNotExist
+		);
+	})
+}
+
+#[test]
+fn offer_claim_failed_when_not_claim_owner() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+
+		assert_noop!(
+			PoeModule::offer_claim(Origin::signed(2), claim, 3, None),
+			Error::<Test>::NotClaimOwner
+		);
+	})
+}
+
+#[test]
+fn offer_claim_failed_when_already_offered() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+		let _ = PoeModule::offer_claim(Origin::signed(1), claim, 2, None);
+
+		assert_noop!(
+			PoeModule::offer_claim(Origin::signed(1), claim, 3, None),
+			Error::<Test>::TransferAlreadyOffered
+		);
+	})
+}
+
+#[test]
+fn offer_claim_failed_with_past_expiry() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
+		let claim = claim_of(&[0, 1]);
 
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+		System::set_block_number(5);
 
-		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+		assert_noop!(
+			PoeModule::offer_claim(Origin::signed(1), claim, 2, Some(4)),
+			Error::<Test>::InvalidExpiry
+		);
+	})
+}
+
+#[test]
+fn accept_claim_failed_when_not_recipient() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+		let _ = PoeModule::offer_claim(Origin::signed(1), claim, 2, None);
+
+		assert_noop!(
+			PoeModule::accept_claim(Origin::signed(3), claim),
+			Error::<Test>::NotTransferRecipient
+		);
+	})
+}
+
+#[test]
+fn accept_claim_failed_when_offer_expired() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+		let _ = PoeModule::offer_claim(Origin::signed(1), claim, 2, Some(3));
+		System::set_block_number(4);
+
+		assert_noop!(
+			PoeModule::accept_claim(Origin::signed(2), claim),
+			Error::<Test>::TransferOfferExpired
+		);
+	})
+}
+
+#[test]
+fn accept_claim_failed_when_recipient_has_too_many_claims() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[u8::MAX]);
+		for i in 0..MaxClaimsPerAccount::get() as u8 {
+			let _ = PoeModule::create_claim(Origin::signed(2), claim_of(&[i]));
+		}
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+		let _ = PoeModule::offer_claim(Origin::signed(1), claim, 2, None);
+
+		assert_noop!(
+			PoeModule::accept_claim(Origin::signed(2), claim),
+			Error::<Test>::TooManyClaims
+		);
+	})
+}
+
+#[test]
+fn accept_claim_failed_when_recipient_has_not_enough_balance() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+		let _ = PoeModule::offer_claim(Origin::signed(1), claim, 4, None);
+
+		assert_noop!(
+			PoeModule::accept_claim(Origin::signed(4), claim),
+			Error::<Test>::NotEnoughBalance
+		);
+	})
+}
+
+#[test]
+fn reject_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+		let _ = PoeModule::offer_claim(Origin::signed(1), claim, 2, None);
+
+		assert_noop!(
+			PoeModule::reject_claim(Origin::signed(3), claim),
+			Error::<Test>::NotTransferRecipient
+		);
+		assert_ok!(PoeModule::reject_claim(Origin::signed(2), claim));
+		assert_eq!(PoeModule::pending_transfers(&claim), None);
+		assert_eq!(Proofs::<Test>::get(&claim).unwrap().owner, 1);
+		assert_noop!(
+			PoeModule::accept_claim(Origin::signed(2), claim),
+			Error::<Test>::NoPendingTransfer
+		);
+	})
+}
+
+#[test]
+fn cancel_offer_works() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+		let _ = PoeModule::offer_claim(Origin::signed(1), claim, 2, None);
+
+		assert_noop!(
+			PoeModule::cancel_offer(Origin::signed(2), claim),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::cancel_offer(Origin::signed(1), claim));
+		assert_eq!(PoeModule::pending_transfers(&claim), None);
+		assert_noop!(
+			PoeModule::cancel_offer(Origin::signed(1), claim),
+			Error::<Test>::NoPendingTransfer
+		);
+	})
+}
+
+#[test]
+fn revoke_claim_drops_pending_offer() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+		let _ = PoeModule::offer_claim(Origin::signed(1), claim, 2, None);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+		assert_eq!(PoeModule::pending_transfers(&claim), None);
+	})
+}
+
+#[test]
+fn create_expiring_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		assert_ok!(PoeModule::create_expiring_claim(Origin::signed(1), claim, 3));
+
+		let deposit = PoeModule::claim_deposit(claim.encoded_size() + 3u64.encoded_size());
+		assert_eq!(Proofs::<Test>::get(&claim).unwrap().deposit, deposit);
+		assert_eq!(Balances::reserved_balance(1), deposit);
+		assert_eq!(PoeModule::claim_expiry(&claim), Some(3));
+		assert_eq!(PoeModule::expiry_schedule(3).into_inner(), vec![claim]);
+
+		System::set_block_number(2);
+		PoeModule::on_initialize(2);
+		assert!(Proofs::<Test>::contains_key(&claim));
+
+		System::set_block_number(3);
+		PoeModule::on_initialize(3);
+		assert_eq!(Proofs::<Test>::get(&claim), None);
+		assert_eq!(PoeModule::claim_expiry(&claim), None);
+		assert!(PoeModule::expiry_schedule(3).is_empty());
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(PoeModule::claim_count(1), 0);
+		System::assert_last_event(Event::PoeModule(crate::Event::ClaimExpired(1, claim)));
+	})
+}
+
+#[test]
+fn create_expiring_claim_failed_with_past_expiry() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+
+		assert_noop!(
+			PoeModule::create_expiring_claim(Origin::signed(1), claim_of(&[0, 1]), 5),
+			Error::<Test>::InvalidExpiry
+		);
+	})
+}
+
+#[test]
+fn create_expiring_claim_failed_when_block_is_full() {
+	new_test_ext().execute_with(|| {
+		for i in 0..MaxExpiriesPerBlock::get() as u8 {
+			assert_ok!(PoeModule::create_expiring_claim(Origin::signed(1), claim_of(&[i]), 3));
+		}
+
+		assert_noop!(
+			PoeModule::create_expiring_claim(Origin::signed(2), claim_of(&[u8::MAX]), 3),
+			Error::<Test>::TooManyExpiries
+		);
+	})
+}
+
+#[test]
+fn renew_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::create_expiring_claim(Origin::signed(1), claim, 3);
+
+		System::set_block_number(2);
+		assert_ok!(PoeModule::renew_claim(Origin::signed(1), claim, 6));
+		assert_eq!(PoeModule::claim_expiry(&claim), Some(6));
+		assert!(PoeModule::expiry_schedule(3).is_empty());
+		assert_eq!(PoeModule::expiry_schedule(6).into_inner(), vec![claim]);
+		System::assert_last_event(Event::PoeModule(crate::Event::ClaimRenewed(1, claim, 6)));
+
+		PoeModule::on_initialize(3);
+		assert!(Proofs::<Test>::contains_key(&claim));
+
+		PoeModule::on_initialize(6);
+		assert_eq!(Proofs::<Test>::get(&claim), None);
+	})
+}
+
+#[test]
+fn renew_claim_failed_with_invalid_claim() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		let permanent = claim_of(&[2, 3]);
+
+		let _ = PoeModule::create_expiring_claim(Origin::signed(1), claim, 3);
+		let _ = PoeModule::create_claim(Origin::signed(1), permanent);
+
+		assert_noop!(
+			PoeModule::renew_claim(Origin::signed(2), claim, 6),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_noop!(
+			PoeModule::renew_claim(Origin::signed(1), claim, 3),
+			Error::<Test>::InvalidExpiry
+		);
+		assert_noop!(
+			PoeModule::renew_claim(Origin::signed(1), permanent, 6),
+			Error::<Test>::ClaimNotExpiring
+		);
+	})
+}
+
+#[test]
+fn revoke_claim_drops_expiry() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::create_expiring_claim(Origin::signed(1), claim, 3);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+		assert_eq!(PoeModule::claim_expiry(&claim), None);
+		assert!(PoeModule::expiry_schedule(3).is_empty());
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+		PoeModule::on_initialize(3);
+		assert!(Proofs::<Test>::contains_key(&claim));
+	})
+}
+
+#[test]
+fn claim_history_records_ownership_changes() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		assert_eq!(PoeModule::claim_history(&claim), None);
+
+		System::set_block_number(1);
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
 		assert_eq!(
-			Proofs::<Test>::get(&claim),
-			Some((2, <frame_system::Pallet<Test>>::block_number()))
-		)
+			PoeModule::claim_history(&claim),
+			Some(Provenance::Active(vec![entry(None, Some(1), 1, ClaimAction::Created)]))
+		);
+
+		for (block, (from, to)) in [(1, 2), (2, 1), (1, 2)].into_iter().enumerate() {
+			System::set_block_number(block as u64 + 2);
+			let _ = PoeModule::offer_claim(Origin::signed(from), claim, to, None);
+			assert_ok!(PoeModule::accept_claim(Origin::signed(to), claim));
+		}
+
+		// The creation entry was dropped to keep the last `MaxHistoryLength` entries.
+		assert_eq!(
+			PoeModule::claim_history(&claim),
+			Some(Provenance::Active(vec![
+				entry(Some(1), Some(2), 2, ClaimAction::Transferred),
+				entry(Some(2), Some(1), 3, ClaimAction::Transferred),
+				entry(Some(1), Some(2), 4, ClaimAction::Transferred),
+			]))
+		);
+	})
+}
+
+#[test]
+fn removing_a_claim_refunds_the_full_deposit() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+
+		let issuance = Balances::total_issuance();
+		assert_eq!(Balances::free_balance(1), 1_000 - deposit_of(&claim));
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 1_000);
+		assert_eq!(Balances::total_issuance(), issuance);
 	})
 }
 
 #[test]
-fn transfer_claim_failed_when_claim_is_too_long() {
+fn claim_deposit_covers_history() {
 	new_test_ext().execute_with(|| {
-		let limit_size = MaxClaimLength::get() as usize;
+		let history_len = BoundedVec::<HistoryEntry<Test>, MaxHistoryLength>::max_encoded_len();
+
+		assert_eq!(
+			PoeModule::claim_deposit(0),
+			ClaimDepositBase::get() + ClaimDepositPerByte::get() * history_len as u64
+		);
+	})
+}
+
+#[test]
+fn claim_history_returns_tombstone_after_removal() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		let expiring = claim_of(&[2, 3]);
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+		let _ = PoeModule::create_expiring_claim(Origin::signed(1), expiring, 3);
+
+		System::set_block_number(2);
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+		assert_eq!(
+			PoeModule::claim_history(&claim),
+			Some(Provenance::Tombstone(entry(Some(1), None, 2, ClaimAction::Revoked)))
+		);
+		assert!(!crate::History::<Test>::contains_key(&claim));
+
+		System::set_block_number(3);
+		PoeModule::on_initialize(3);
+		assert_eq!(
+			PoeModule::claim_history(&expiring),
+			Some(Provenance::Tombstone(entry(Some(1), None, 3, ClaimAction::Expired)))
+		);
+
+		let _ = PoeModule::create_claim(Origin::signed(2), claim);
+		assert_eq!(
+			PoeModule::claim_history(&claim),
+			Some(Provenance::Active(vec![entry(None, Some(2), 3, ClaimAction::Created)]))
+		);
+	})
+}
+
+#[test]
+fn set_claim_metadata_works() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		let description = b"release notes".to_vec();
+		let content_type = b"text/md".to_vec();
+		let uri = b"ipfs://notes".to_vec();
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+
+		assert_ok!(PoeModule::set_claim_metadata(
+			Origin::signed(1),
+			claim,
+			bounded(&description),
+			bounded(&content_type),
+			Some(bounded(&uri))
+		));
+
+		let info = Proofs::<Test>::get(&claim).unwrap();
+		assert_eq!(info.description.into_inner(), description);
+		assert_eq!(info.content_type.into_inner(), content_type);
+		assert_eq!(info.uri.map(|uri| uri.into_inner()), Some(uri.clone()));
+
+		let deposit = PoeModule::claim_deposit(
+			claim.encoded_size() + description.len() + content_type.len() + uri.len(),
+		);
+		assert_eq!(info.deposit, deposit);
+		assert_eq!(Balances::reserved_balance(1), deposit);
+
+		assert_ok!(PoeModule::set_claim_metadata(
+			Origin::signed(1),
+			claim,
+			Default::default(),
+			Default::default(),
+			None
+		));
+		assert_eq!(Balances::reserved_balance(1), deposit_of(&claim));
+	})
+}
+
+#[test]
+fn set_claim_metadata_failed_when_not_claim_owner() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
 
 		assert_noop!(
-			PoeModule::transfer_claim(
-				Origin::signed(1),
-				vec![0u8; limit_size.checked_add(1).unwrap()],
-				2
+			PoeModule::set_claim_metadata(
+				Origin::signed(2),
+				claim,
+				Default::default(),
+				Default::default(),
+				None
 			),
-			Error::<Test>::ClaimOverflow
+			Error::<Test>::NotClaimOwner
 		);
 	})
 }
 
 #[test]
-fn transfer_claim_failed_when_claim_not_exist() {
+fn set_claim_metadata_works_up_to_the_length_limit() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		let description = vec![0u8; MaxDescriptionLength::get() as usize];
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+
+		assert_ok!(PoeModule::set_claim_metadata(
+			Origin::signed(1),
+			claim,
+			bounded(&description),
+			Default::default(),
+			None
+		));
+		assert_eq!(Proofs::<Test>::get(&claim).unwrap().description.into_inner(), description);
+	})
+}
+
+#[test]
+fn set_claim_metadata_fails_to_decode_past_the_length_limit() {
+	let claim = claim_of(&[0, 1]);
+	let call_index = crate::Call::<Test>::set_claim_metadata {
+		claim,
+		description: Default::default(),
+		content_type: Default::default(),
+		uri: None,
+	}
+	.encode()[0];
+
+	let max = MaxDescriptionLength::get() as usize;
+	for (len, decodes) in [(max - 1, true), (max, true), (max + 1, false)] {
+		let encoded =
+			(call_index, claim, vec![0u8; len], Vec::<u8>::new(), None::<Vec<u8>>).encode();
+		assert_eq!(
+			crate::Call::<Test>::decode(&mut &encoded[..]).is_ok(),
+			decodes,
+			"description of {} bytes",
+			len
+		);
+	}
+
+	let oversized = vec![0u8; MaxUriLength::get() as usize + 1];
+	let encoded = (call_index, claim, Vec::<u8>::new(), Vec::<u8>::new(), Some(oversized)).encode();
+	assert!(crate::Call::<Test>::decode(&mut &encoded[..]).is_err());
+}
+
+#[test]
+fn storage_has_max_encoded_len() {
+	for info in PoeModule::storage_info() {
+		assert!(info.max_size.is_some(), "{:?} is unbounded", info.storage_name);
+	}
+}
+
+#[test]
+fn force_revoke_claim_works() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
 
 		assert_noop!(
-			PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2),
+			PoeModule::force_revoke_claim(Origin::signed(1), claim),
+			frame_support::error::BadOrigin
+		);
+
+		assert_ok!(PoeModule::force_revoke_claim(Origin::root(), claim));
+		assert_eq!(Proofs::<Test>::get(&claim), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(PoeModule::claim_count(1), 0);
+		assert_eq!(
+			PoeModule::claim_history(&claim),
+			Some(Provenance::Tombstone(entry(Some(1), None, 0, ClaimAction::Revoked)))
+		);
+
+		assert_noop!(
+			PoeModule::force_revoke_claim(Origin::root(), claim),
 			Error::<Test>::ClaimNotExist
 		);
 	})
 }
 
 #[test]
-fn transfer_claim_failed_when_not_claim_owner() {
+fn force_transfer_claim_works() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
+		let claim = claim_of(&[0, 1]);
 
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
+		let _ = PoeModule::offer_claim(Origin::signed(1), claim, 3, None);
 
 		assert_noop!(
-			PoeModule::transfer_claim(Origin::signed(2), claim.clone(), 3),
-			Error::<Test>::NotClaimOwner
+			PoeModule::force_transfer_claim(Origin::signed(2), claim, 2),
+			frame_support::error::BadOrigin
+		);
+		assert_noop!(
+			PoeModule::force_transfer_claim(Origin::root(), claim, 4),
+			Error::<Test>::NotEnoughBalance
+		);
+
+		assert_ok!(PoeModule::force_transfer_claim(Origin::root(), claim, 2));
+		assert_eq!(Proofs::<Test>::get(&claim).unwrap().owner, 2);
+		assert_eq!(PoeModule::pending_transfers(&claim), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), deposit_of(&claim));
+		assert_eq!(PoeModule::claims_of(&2), vec![claim]);
+	})
+}
+
+#[test]
+fn approve_and_remove_operator_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let permissions = Permissions::CREATE | Permissions::REVOKE;
+
+		assert_ok!(PoeModule::approve_operator(Origin::signed(1), 3, permissions));
+		assert_eq!(PoeModule::operators(1, 3), Some(permissions));
+		System::assert_last_event(Event::PoeModule(crate::Event::OperatorApproved(
+			1,
+			3,
+			permissions,
+		)));
+
+		assert_ok!(PoeModule::remove_operator(Origin::signed(1), 3));
+		assert_eq!(PoeModule::operators(1, 3), None);
+		System::assert_last_event(Event::PoeModule(crate::Event::OperatorRemoved(1, 3)));
+
+		assert_noop!(PoeModule::remove_operator(Origin::signed(1), 3), Error::<Test>::NotOperator);
+		assert_noop!(
+			PoeModule::approve_operator(Origin::signed(1), 1, Permissions::ALL),
+			Error::<Test>::OperatorIsOwner
+		);
+		assert_noop!(
+			PoeModule::approve_operator(Origin::signed(1), 3, Permissions::default()),
+			Error::<Test>::EmptyPermissions
+		);
+	})
+}
+
+#[test]
+fn operator_manages_claims_on_behalf_of_owner() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let claim = claim_of(&[0, 1]);
+
+		let _ = PoeModule::approve_operator(Origin::signed(1), 3, Permissions::ALL);
+
+		assert_ok!(PoeModule::operator_create_claim(Origin::signed(3), 1, claim));
+		assert_eq!(Proofs::<Test>::get(&claim).unwrap().owner, 1);
+		assert_eq!(Balances::reserved_balance(1), deposit_of(&claim));
+		assert_eq!(Balances::reserved_balance(3), 0);
+		System::assert_has_event(Event::PoeModule(crate::Event::ClaimCreated(1, claim)));
+		System::assert_last_event(Event::PoeModule(crate::Event::OperatorActed(3, 1, claim)));
+
+		assert_ok!(PoeModule::operator_offer_claim(Origin::signed(3), claim, 2, None));
+		assert_eq!(PoeModule::pending_transfers(&claim).unwrap().to, 2);
+		System::assert_has_event(Event::PoeModule(crate::Event::ClaimTransferOffered(1, 2, claim)));
+
+		assert_ok!(PoeModule::operator_revoke_claim(Origin::signed(3), claim));
+		assert_eq!(Proofs::<Test>::get(&claim), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		System::assert_has_event(Event::PoeModule(crate::Event::ClaimRevoked(1, claim)));
+		System::assert_last_event(Event::PoeModule(crate::Event::OperatorActed(3, 1, claim)));
+	})
+}
+
+#[test]
+fn operator_failed_without_permission() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		assert_noop!(
+			PoeModule::operator_create_claim(Origin::signed(3), 1, claim),
+			Error::<Test>::NotOperator
+		);
+
+		let _ = PoeModule::approve_operator(Origin::signed(1), 3, Permissions::CREATE);
+		assert_ok!(PoeModule::operator_create_claim(Origin::signed(3), 1, claim));
+
+		assert_noop!(
+			PoeModule::operator_revoke_claim(Origin::signed(3), claim),
+			Error::<Test>::NotOperator
 		);
+		assert_noop!(
+			PoeModule::operator_offer_claim(Origin::signed(3), claim, 2, None),
+			Error::<Test>::NotOperator
+		);
+		assert_noop!(
+			PoeModule::operator_revoke_claim(Origin::signed(2), claim),
+			Error::<Test>::NotOperator
+		);
+	})
+}
+
+#[test]
+fn migrate_rehashes_raw_claims_without_deposit() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<PoeModule>();
+
+		let raw_claim = vec![0u8, 1, 2];
+		let proof: (u64, u64) = (1, 0);
+		put_storage_value(
+			b"PoeModule",
+			b"Proofs",
+			&Blake2_128Concat::hash(&raw_claim.encode()),
+			proof,
+		);
+
+		PoeModule::on_runtime_upgrade();
+
+		let info = Proofs::<Test>::get(claim_of(&raw_claim)).unwrap();
+		assert_eq!((info.owner, info.deposit, info.created_at, info.transferred_at), (1, 0, 0, 0));
+		assert!(info.description.is_empty());
+		assert_eq!(info.uri, None);
+		assert_eq!(Proofs::<Test>::iter().count(), 1);
+		assert_eq!(PoeModule::claims_of(&1), vec![claim_of(&raw_claim)]);
+		assert_eq!(PoeModule::claim_count(1), 1);
+		assert_eq!(PoeModule::on_chain_storage_version(), 4);
 	})
 }
@@ -0,0 +1,276 @@
+//! Weights for pallet_poe
+//!
+//! These are hand estimates, not benchmark results. The database reads and writes of each call
+//! are counted from the storage items it touches, listed above each function, while the
+//! execution time is a rough guess. Replace this file with the output of the benchmarks in
+//! `benchmarking.rs` once they have been run on reference hardware.
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_poe.
+pub trait WeightInfo {
+	fn create_claim() -> Weight;
+	fn create_merkle_claim() -> Weight;
+	fn create_expiring_claim() -> Weight;
+	fn renew_claim() -> Weight;
+	fn on_initialize(c: u32) -> Weight;
+	fn propose_claim(a: u32) -> Weight;
+	fn attest_claim(a: u32) -> Weight;
+	fn cancel_proposal() -> Weight;
+	fn approve_operator() -> Weight;
+	fn remove_operator() -> Weight;
+	fn revoke_claim() -> Weight;
+	fn revoke_all_claims(c: u32) -> Weight;
+	fn offer_claim() -> Weight;
+	fn accept_claim() -> Weight;
+	fn reject_claim() -> Weight;
+	fn cancel_offer() -> Weight;
+	fn set_claim_metadata(d: u32, t: u32, u: u32) -> Weight;
+}
+
+/// Estimated weights for pallet_poe, using the database weights of the runtime.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Reads: Proofs, PendingClaims, AccountClaimCount, System::Account
+	// Writes: Proofs, AccountClaimCount, AccountClaims, System::Account, History, Tombstones
+	fn create_claim() -> Weight {
+		(38_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
+	// Reads: Proofs, PendingClaims, AccountClaimCount, System::Account
+	// Writes: Proofs, AccountClaimCount, AccountClaims, System::Account, MerkleClaims, History,
+	//   Tombstones
+	fn create_merkle_claim() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
+	// Reads: ExpirySchedule, Proofs, PendingClaims, AccountClaimCount, System::Account
+	// Writes: ExpirySchedule, ClaimExpiry, Proofs, AccountClaimCount, AccountClaims,
+	//   System::Account, History, Tombstones
+	fn create_expiring_claim() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(8))
+	}
+	// Reads: Proofs, ClaimExpiry, ExpirySchedule (old and new block)
+	// Writes: ClaimExpiry, ExpirySchedule (old and new block)
+	fn renew_claim() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Reads: ExpirySchedule, Balances::TotalIssuance; per claim: Proofs, System::Account,
+	//   AccountClaims, AccountClaimCount
+	// Writes: ExpirySchedule, Balances::TotalIssuance; per claim: ClaimExpiry, Proofs,
+	//   System::Account, AccountClaims, AccountClaimCount, PendingTransfers, MerkleClaims, History,
+	//   Tombstones
+	fn on_initialize(c: u32) -> Weight {
+		(3_000_000 as Weight)
+			.saturating_add((36_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().writes((9 as Weight).saturating_mul(c as Weight)))
+	}
+	// Reads: Proofs, PendingClaims, System::Account
+	// Writes: PendingClaims, System::Account
+	fn propose_claim(a: u32) -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add((250_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Reads: PendingClaims, Proofs, AccountClaimCount, System::Account
+	// Writes: PendingClaims, Proofs, AccountClaimCount, AccountClaims, System::Account, History,
+	//   Tombstones
+	fn attest_claim(a: u32) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
+	// Reads: PendingClaims, System::Account
+	// Writes: PendingClaims, System::Account
+	fn cancel_proposal() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Writes: Operators
+	fn approve_operator() -> Weight {
+		(17_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Reads: Operators
+	// Writes: Operators
+	fn remove_operator() -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Reads: Proofs, System::Account, Balances::TotalIssuance, AccountClaims, AccountClaimCount,
+	//   ClaimExpiry, ExpirySchedule
+	// Writes: Proofs, System::Account, Balances::TotalIssuance, AccountClaims, AccountClaimCount,
+	//   ClaimExpiry, ExpirySchedule, PendingTransfers, MerkleClaims, History, Tombstones
+	fn revoke_claim() -> Weight {
+		(46_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(11))
+	}
+	// Reads: System::Account, Balances::TotalIssuance, AccountClaimCount; per claim: AccountClaims,
+	//   Proofs, ClaimExpiry, ExpirySchedule
+	// Writes: System::Account, Balances::TotalIssuance, AccountClaimCount; per claim:
+	//   AccountClaims, Proofs, ClaimExpiry, ExpirySchedule, PendingTransfers, MerkleClaims,
+	//   History, Tombstones
+	fn revoke_all_claims(c: u32) -> Weight {
+		(12_000_000 as Weight)
+			.saturating_add((35_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(T::DbWeight::get().writes((8 as Weight).saturating_mul(c as Weight)))
+	}
+	// Reads: Proofs, PendingTransfers
+	// Writes: PendingTransfers
+	fn offer_claim() -> Weight {
+		(22_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Reads: PendingTransfers, Proofs, History, System::Account (both), AccountClaimCount (both),
+	//   AccountClaims (previous owner)
+	// Writes: PendingTransfers, Proofs, History, System::Account (both), AccountClaimCount (both),
+	//   AccountClaims (both)
+	fn accept_claim() -> Weight {
+		(55_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8))
+			.saturating_add(T::DbWeight::get().writes(9))
+	}
+	// Reads: PendingTransfers, Proofs
+	// Writes: PendingTransfers
+	fn reject_claim() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Reads: Proofs, PendingTransfers
+	// Writes: PendingTransfers
+	fn cancel_offer() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Reads: Proofs, MerkleClaims, ClaimExpiry, System::Account
+	// Writes: Proofs, System::Account
+	fn set_claim_metadata(d: u32, t: u32, u: u32) -> Weight {
+		(31_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add((2_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add((2_000 as Weight).saturating_mul(u as Weight))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn create_claim() -> Weight {
+		(38_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(6))
+	}
+	fn create_merkle_claim() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(7))
+	}
+	fn create_expiring_claim() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().writes(8))
+	}
+	fn renew_claim() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn on_initialize(c: u32) -> Weight {
+		(3_000_000 as Weight)
+			.saturating_add((36_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(2))
+			.saturating_add(RocksDbWeight::get().writes((9 as Weight).saturating_mul(c as Weight)))
+	}
+	fn propose_claim(a: u32) -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add((250_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn attest_claim(a: u32) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(7))
+	}
+	fn cancel_proposal() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn approve_operator() -> Weight {
+		(17_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn remove_operator() -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn revoke_claim() -> Weight {
+		(46_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7))
+			.saturating_add(RocksDbWeight::get().writes(11))
+	}
+	fn revoke_all_claims(c: u32) -> Weight {
+		(12_000_000 as Weight)
+			.saturating_add((35_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3))
+			.saturating_add(RocksDbWeight::get().writes((8 as Weight).saturating_mul(c as Weight)))
+	}
+	fn offer_claim() -> Weight {
+		(22_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn accept_claim() -> Weight {
+		(55_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8))
+			.saturating_add(RocksDbWeight::get().writes(9))
+	}
+	fn reject_claim() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn cancel_offer() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn set_claim_metadata(d: u32, t: u32, u: u32) -> Weight {
+		(31_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add((2_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add((2_000 as Weight).saturating_mul(u as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+}
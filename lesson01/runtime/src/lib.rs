@@ -96,12 +96,12 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// The version of the runtime specification. A full node will not attempt to use its native
 	//   runtime in substitute for the on-chain Wasm runtime unless all of `spec_name`,
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
-	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
-	//   the compatible custom types.
-	spec_version: 100,
+	// This value started at 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
+	//   the compatible custom types, and is bumped with every runtime upgrade.
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 /// This determines the average expected block time that we are targeting.
@@ -275,13 +275,32 @@ impl pallet_sudo::Config for Runtime {
 }
 
 parameter_types! {
-	pub const MaxClaimLength: u8 = 10;
+	pub const ClaimDepositBase: Balance = 10_000;
+	pub const ClaimDepositPerByte: Balance = 100;
+	pub const MaxDescriptionLength: u32 = 256;
+	pub const MaxContentTypeLength: u32 = 64;
+	pub const MaxUriLength: u32 = 256;
+	pub const MaxClaimsPerAccount: u32 = 1_000;
+	pub const MaxExpiriesPerBlock: u32 = 100;
+	pub const MaxAttesters: u32 = 16;
+	pub const MaxHistoryLength: u32 = 32;
 }
 
 /// Configure the pallet-poe in pallets/poe.
 impl pallet_poe::Config for Runtime {
 	type Event = Event;
-	type MaxClaimLength = MaxClaimLength;
+	type Currency = Balances;
+	type ClaimDepositBase = ClaimDepositBase;
+	type ClaimDepositPerByte = ClaimDepositPerByte;
+	type MaxDescriptionLength = MaxDescriptionLength;
+	type MaxContentTypeLength = MaxContentTypeLength;
+	type MaxUriLength = MaxUriLength;
+	type MaxClaimsPerAccount = MaxClaimsPerAccount;
+	type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+	type MaxAttesters = MaxAttesters;
+	type MaxHistoryLength = MaxHistoryLength;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type WeightInfo = pallet_poe::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -462,6 +481,55 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_poe_rpc_runtime_api::PoeApi<Block, AccountId, Hash, Balance, BlockNumber> for Runtime {
+		fn claim_of(
+			claim: Hash,
+		) -> Option<pallet_poe_rpc_runtime_api::ClaimDetails<AccountId, Balance, BlockNumber>> {
+			PoeModule::proofs(claim).map(|info| pallet_poe_rpc_runtime_api::ClaimDetails {
+				owner: info.owner,
+				deposit: info.deposit,
+				created_at: info.created_at,
+				transferred_at: info.transferred_at,
+				description: info.description.into_inner(),
+				content_type: info.content_type.into_inner(),
+				uri: info.uri.map(|uri| uri.into_inner()),
+			})
+		}
+
+		fn claims_of(owner: AccountId) -> Vec<Hash> {
+			PoeModule::claims_of(&owner)
+		}
+
+		fn verify_inclusion(root: Hash, leaf: Hash, proof: Vec<Hash>) -> bool {
+			PoeModule::verify_inclusion(root, leaf, proof)
+		}
+
+		fn claim_history(
+			claim: Hash,
+		) -> Option<pallet_poe_rpc_runtime_api::ClaimHistory<AccountId, BlockNumber>> {
+			use pallet_poe_rpc_runtime_api::{ClaimAction, ClaimHistory, HistoryEntry};
+
+			let entry = |entry: pallet_poe::HistoryEntry<Runtime>| HistoryEntry {
+				from: entry.from,
+				to: entry.to,
+				block: entry.block,
+				action: match entry.action {
+					pallet_poe::ClaimAction::Created => ClaimAction::Created,
+					pallet_poe::ClaimAction::Transferred => ClaimAction::Transferred,
+					pallet_poe::ClaimAction::Revoked => ClaimAction::Revoked,
+					pallet_poe::ClaimAction::Expired => ClaimAction::Expired,
+				},
+			};
+
+			PoeModule::claim_history(&claim).map(|provenance| match provenance {
+				pallet_poe::Provenance::Active(history) =>
+					ClaimHistory::Active(history.into_iter().map(entry).collect()),
+				pallet_poe::Provenance::Tombstone(tombstone) =>
+					ClaimHistory::Tombstone(entry(tombstone)),
+			})
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -479,7 +547,7 @@ impl_runtime_apis! {
 			list_benchmark!(list, extra, frame_system, SystemBench::<Runtime>);
 			list_benchmark!(list, extra, pallet_balances, Balances);
 			list_benchmark!(list, extra, pallet_timestamp, Timestamp);
-			list_benchmark!(list, extra, pallet_template, PoeModule);
+			list_benchmark!(list, extra, pallet_poe, PoeModule);
 
 			let storage_info = AllPalletsWithSystem::storage_info();
 
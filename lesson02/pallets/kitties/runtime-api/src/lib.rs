@@ -0,0 +1,16 @@
+//! Runtime API definition for the kitties pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait KittiesApi<AccountId, KittyIndex> where
+		AccountId: Codec,
+		KittyIndex: Codec,
+	{
+		/// All kitties owned by `owner`.
+		fn kitties_of(owner: AccountId) -> Vec<KittyIndex>;
+	}
+}
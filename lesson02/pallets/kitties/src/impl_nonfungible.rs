@@ -0,0 +1,78 @@
+//! Implementations of the `nonfungible` traits, so that other pallets can treat kitties as a
+//! single class of NFTs.
+
+use super::*;
+use codec::Encode;
+use frame_support::{
+	dispatch::DispatchResult,
+	ensure,
+	traits::{
+		tokens::nonfungible::{Inspect, Mutate, Transfer},
+		Get,
+	},
+};
+use sp_runtime::traits::{Bounded, Saturating, Zero};
+use sp_std::vec::Vec;
+
+impl<T: Config> Inspect<T::AccountId> for Pallet<T> {
+	type InstanceId = T::KittyIndex;
+
+	fn owner(kitty_id: &T::KittyIndex) -> Option<T::AccountId> {
+		Owner::<T>::get(kitty_id)
+	}
+
+	/// Exposes the kitty's `dna` and `price`, SCALE encoded.
+	fn attribute(kitty_id: &T::KittyIndex, key: &[u8]) -> Option<Vec<u8>> {
+		let kitty = Kitties::<T>::get(kitty_id)?;
+		match key {
+			b"dna" => Some(kitty.dna.encode()),
+			b"price" => Some(kitty.price.encode()),
+			_ => None,
+		}
+	}
+
+	fn can_transfer(kitty_id: &T::KittyIndex) -> bool {
+		Kitties::<T>::contains_key(kitty_id) && !KittyAuction::<T>::contains_key(kitty_id)
+	}
+}
+
+impl<T: Config> Transfer<T::AccountId> for Pallet<T> {
+	fn transfer(kitty_id: &T::KittyIndex, destination: &T::AccountId) -> DispatchResult {
+		let owner = Owner::<T>::get(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
+		Self::do_transfer(*kitty_id, owner, destination.clone())
+	}
+}
+
+impl<T: Config> Mutate<T::AccountId> for Pallet<T> {
+	/// Mint a generation 0 kitty with a zero deposit. Kitty ids are handed out in order,
+	/// so `kitty_id` must be the next unused id. Like `create`, this only commits to the kitty
+	/// when `RevealDelay` is set, and the kitty exists once it is revealed.
+	fn mint_into(kitty_id: &T::KittyIndex, who: &T::AccountId) -> DispatchResult {
+		let next_id = Pallet::<T>::kitties_count().unwrap_or_default();
+		ensure!(*kitty_id == next_id, Error::<T>::InvalidKittyIndex);
+		ensure!(next_id != T::KittyIndex::max_value(), Error::<T>::KittiesCountOverflow);
+
+		Pallet::<T>::hold_deposit(next_id, who, Zero::zero())?;
+
+		let now = <frame_system::Pallet<T>>::block_number();
+		Pallet::<T>::conceive(
+			next_id,
+			PendingKitty {
+				owner: who.clone(),
+				price: Zero::zero(),
+				parents: None,
+				parent_dna: None,
+				generation: 0,
+				committed_at: now,
+				reveal_at: now.saturating_add(T::RevealDelay::get()),
+			},
+		);
+
+		Ok(())
+	}
+
+	fn burn_from(kitty_id: &T::KittyIndex) -> DispatchResult {
+		let owner = Owner::<T>::get(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
+		Pallet::<T>::do_burn(*kitty_id, owner)
+	}
+}
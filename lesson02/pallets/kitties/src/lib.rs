@@ -14,38 +14,127 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+mod impl_nonfungible;
+
+pub mod migrations;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::{
 		dispatch::DispatchResult,
 		pallet_prelude::*,
-		traits::{tokens::ExistenceRequirement, Currency, Randomness, ReservableCurrency},
+		traits::{
+			tokens::ExistenceRequirement, BalanceStatus, Currency, NamedReservableCurrency,
+			Randomness, ReservableCurrency, StorageVersion,
+		},
+		transactional, PalletId,
 	};
 	use frame_system::pallet_prelude::*;
 	use scale_info::TypeInfo;
 	use sp_io::hashing::blake2_128;
-	use sp_runtime::traits::{AtLeast32BitUnsigned, Bounded};
+	use sp_runtime::{
+		traits::{AccountIdConversion, AtLeast32BitUnsigned, Bounded, Saturating, Zero},
+		Permill,
+	};
+	use sp_std::vec::Vec;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(6);
 
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
-		type Currency: ReservableCurrency<Self::AccountId>;
+		type Currency: NamedReservableCurrency<Self::AccountId, ReserveIdentifier = [u8; 8]>;
 		type KittyIndex: AtLeast32BitUnsigned + Copy + Parameter + Default + Bounded;
+		#[pallet::constant]
+		type MaxAuctionsPerBlock: Get<u32>;
+		#[pallet::constant]
+		type BreedCooldown: Get<Self::BlockNumber>;
+		/// Blocks between committing to a new kitty and revealing its DNA. Zero generates the
+		/// DNA right away instead. Must be longer than the lookback of `Randomness`, or the
+		/// randomness recorded for the reveal was already known at commit time.
+		#[pallet::constant]
+		type RevealDelay: Get<Self::BlockNumber>;
+		/// Derives the treasury account that collects the marketplace fee.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+		/// Share of every sale paid to the treasury.
+		#[pallet::constant]
+		type MarketplaceFee: Get<Permill>;
+		/// Share of every sale paid to the kitty's creator, unless the creator is selling.
+		#[pallet::constant]
+		type CreatorRoyalty: Get<Permill>;
+		/// The maximum number of kitties, pending ones included, an account can hold. Every kitty
+		/// takes a named reserve, so this must not exceed the `MaxReserves` of the currency.
+		#[pallet::constant]
+		type MaxKittiesOwned: Get<u32>;
 	}
 
-	type BalanceOf<T> =
+	pub type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
 	#[scale_info(skip_type_params(T))]
 	pub struct Kitty<T: Config> {
-		dna: [u8; 16],
-		price: BalanceOf<T>,
+		pub dna: [u8; 16],
+		pub price: BalanceOf<T>,
+		pub parents: Option<(T::KittyIndex, T::KittyIndex)>,
+		pub generation: u32,
+		pub birth_block: T::BlockNumber,
+		pub traits: KittyTraits,
+		pub ready_at: T::BlockNumber,
+		pub creator: T::AccountId,
+	}
+
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct KittyTraits {
+		pub body: u8,
+		pub eyes: u8,
+		pub accessory: u8,
+	}
+
+	impl KittyTraits {
+		pub fn from_dna(dna: &[u8; 16]) -> Self {
+			Self { body: dna[0], eyes: dna[1], accessory: dna[2] }
+		}
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct PendingKitty<T: Config> {
+		pub owner: T::AccountId,
+		pub price: BalanceOf<T>,
+		pub parents: Option<(T::KittyIndex, T::KittyIndex)>,
+		pub parent_dna: Option<([u8; 16], [u8; 16])>,
+		pub generation: u32,
+		pub committed_at: T::BlockNumber,
+		/// The block whose randomness decides the DNA.
+		pub reveal_at: T::BlockNumber,
+	}
+
+	/// The balance held for a kitty under its named reserve, and the account it is held from.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct KittyDeposit<T: Config> {
+		pub depositor: T::AccountId,
+		pub amount: BalanceOf<T>,
+	}
+
+	pub type AuctionIndex = u32;
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Auction<T: Config> {
+		pub kitty_id: T::KittyIndex,
+		pub seller: T::AccountId,
+		pub reserve: BalanceOf<T>,
+		pub end_block: T::BlockNumber,
+		pub best_bid: Option<(T::AccountId, BalanceOf<T>)>,
 	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::storage]
@@ -61,13 +150,103 @@ pub mod pallet {
 	pub type Owner<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::KittyIndex, Option<T::AccountId>, ValueQuery>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn pending_kitties)]
+	pub type PendingKitties<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, PendingKitty<T>>;
+
+	/// The number of pending kitties to be revealed with the randomness of each block.
+	#[pallet::storage]
+	pub type RevealsDue<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, u32, ValueQuery>;
+
+	/// The randomness recorded at the start of a block that pending kitties are revealed with,
+	/// and the block it has been known since.
+	#[pallet::storage]
+	pub type RevealSeeds<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, (T::Hash, T::BlockNumber)>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn deposits)]
+	pub type Deposits<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, KittyDeposit<T>>;
+
+	#[pallet::storage]
+	pub type OwnedKitties<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::KittyIndex, ()>;
+
+	/// The number of kitty deposits each account holds, one for every kitty it owns or has
+	/// committed to.
+	#[pallet::storage]
+	#[pallet::getter(fn owned_kitties_count)]
+	pub type OwnedKittiesCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn approvals)]
+	pub type Approvals<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
+
+	#[pallet::storage]
+	pub type OperatorApprovals<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::AccountId, ()>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn listings)]
+	pub type Listings<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, BalanceOf<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn auctions_count)]
+	pub type AuctionsCount<T: Config> = StorageValue<_, AuctionIndex, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn auctions)]
+	pub type Auctions<T: Config> = StorageMap<_, Blake2_128Concat, AuctionIndex, Auction<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_auction)]
+	pub type KittyAuction<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, AuctionIndex>;
+
+	#[pallet::storage]
+	pub type AuctionsEnding<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<AuctionIndex, T::MaxAuctionsPerBlock>,
+		ValueQuery,
+	>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		KittyCreate(T::AccountId, T::KittyIndex),
+		KittyCommitted(T::AccountId, T::KittyIndex, T::BlockNumber),
 		KittyTransfer(T::AccountId, T::AccountId, T::KittyIndex),
-		KittyBuy(T::AccountId, T::AccountId, T::KittyIndex),
-		KittySell(T::AccountId, T::AccountId, T::KittyIndex),
+		Approval(T::AccountId, T::AccountId, T::KittyIndex),
+		ApprovalForAll(T::AccountId, T::AccountId, bool),
+		KittyBurned(T::AccountId, T::KittyIndex),
+		KittyListed(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		KittyListingCancelled(T::AccountId, T::KittyIndex),
+		/// Seller, buyer, kitty, price, marketplace fee, creator royalty.
+		KittySold(
+			T::AccountId,
+			T::AccountId,
+			T::KittyIndex,
+			BalanceOf<T>,
+			BalanceOf<T>,
+			BalanceOf<T>,
+		),
+		AuctionCreated(T::AccountId, AuctionIndex, T::KittyIndex, BalanceOf<T>, T::BlockNumber),
+		AuctionBid(T::AccountId, AuctionIndex, BalanceOf<T>),
+		/// Seller, winner, auction, kitty, winning bid, marketplace fee, creator royalty.
+		AuctionSettled(
+			T::AccountId,
+			T::AccountId,
+			AuctionIndex,
+			T::KittyIndex,
+			BalanceOf<T>,
+			BalanceOf<T>,
+			BalanceOf<T>,
+		),
+		AuctionFailed(T::AccountId, AuctionIndex, T::KittyIndex),
+		KittyCancelled(T::AccountId, T::KittyIndex),
 	}
 
 	#[pallet::error]
@@ -77,10 +256,62 @@ pub mod pallet {
 		SameParentIndex,
 		InvalidKittyIndex,
 		NotEnoughBalance,
+		NotListed,
+		BuyerIsOwner,
+		PriceExceedsMaxPrice,
+		AuctionsCountOverflow,
+		InvalidAuctionIndex,
+		KittyInAuction,
+		InvalidEndBlock,
+		TooManyAuctions,
+		AuctionEnded,
+		BidderIsOwner,
+		BidTooLow,
+		KittyOnCooldown,
+		NotPending,
+		RevealTooEarly,
+		RandomnessNotReady,
+		NotApproved,
+		ApproveToCaller,
+		TooManyKitties,
+		RandomnessReady,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			// fix the randomness that kitties due now are revealed with, whenever they are
+			let mut weight = T::DbWeight::get().reads(1);
+			if RevealsDue::<T>::contains_key(now) {
+				RevealSeeds::<T>::insert(now, T::Randomness::random(b"kitty"));
+				weight = weight.saturating_add(T::DbWeight::get().writes(1));
+			}
+
+			// reserve room for the auctions settled in `on_finalize`
+			let count = AuctionsEnding::<T>::decode_len(now).unwrap_or(0) as Weight;
+			weight.saturating_add(T::DbWeight::get().reads_writes(1 + count * 3, 1 + count * 5))
+		}
+
+		fn on_finalize(now: T::BlockNumber) {
+			for auction_id in AuctionsEnding::<T>::take(now) {
+				Self::settle_auction(auction_id);
+			}
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::v1::migrate::<T>()
+				.saturating_add(crate::migrations::v2::migrate::<T>())
+				.saturating_add(crate::migrations::v3::migrate::<T>())
+				.saturating_add(crate::migrations::v4::migrate::<T>())
+				.saturating_add(crate::migrations::v5::migrate::<T>())
+				.saturating_add(crate::migrations::v6::migrate::<T>())
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
+		// the call index is part of the transaction encoding, so existing calls keep their place
+		// and new ones are appended
 		#[pallet::weight(0)]
 		pub fn create(origin: OriginFor<T>, price: BalanceOf<T>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
@@ -93,14 +324,22 @@ pub mod pallet {
 				None => 0u32.into(),
 			};
 
-			let dna = Self::random_value(&who);
-
 			ensure!(T::Currency::can_reserve(&who, price), Error::<T>::NotEnoughBalance);
-			T::Currency::reserve(&who, price)?;
+			Self::hold_deposit(kitty_id, &who, price)?;
 
-			Self::create_kitty(kitty_id, dna, price, &who);
-
-			Self::deposit_event(Event::KittyCreate(who, kitty_id));
+			let now = <frame_system::Pallet<T>>::block_number();
+			Self::conceive(
+				kitty_id,
+				PendingKitty {
+					owner: who,
+					price,
+					parents: None,
+					parent_dna: None,
+					generation: 0,
+					committed_at: now,
+					reveal_at: now.saturating_add(T::RevealDelay::get()),
+				},
+			);
 
 			Ok(())
 		}
@@ -113,21 +352,11 @@ pub mod pallet {
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
-			let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
+			ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyIndex);
 			let kitty_host = Owner::<T>::get(kitty_id).ok_or(Error::<T>::NotOwner)?;
 			ensure!(kitty_host == who, Error::<T>::NotOwner);
 
-			ensure!(
-				T::Currency::can_reserve(&new_owner, kitty.price),
-				Error::<T>::NotEnoughBalance
-			);
-			T::Currency::unreserve(&who, kitty.price);
-			T::Currency::reserve(&new_owner, kitty.price)?;
-			Owner::<T>::insert(kitty_id, Some(new_owner.clone()));
-
-			Self::deposit_event(Event::KittyTransfer(who, new_owner, kitty_id));
-
-			Ok(())
+			Self::do_transfer(kitty_id, who, new_owner)
 		}
 
 		#[pallet::weight(0)]
@@ -150,6 +379,11 @@ pub mod pallet {
 			ensure!(Owner::<T>::get(kitty_id1) == Some(who.clone()), Error::<T>::NotOwner);
 			ensure!(Owner::<T>::get(kitty_id2) == Some(who.clone()), Error::<T>::NotOwner);
 
+			// kitties are ready to breed
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(kitty1.ready_at <= now, Error::<T>::KittyOnCooldown);
+			ensure!(kitty2.ready_at <= now, Error::<T>::KittyOnCooldown);
+
 			// get kitty id
 			let kitty_id = match Self::kitties_count() {
 				Some(id) => {
@@ -159,76 +393,407 @@ pub mod pallet {
 				None => 0u32.into(),
 			};
 
-			// dna generate
-			let dna_1 = kitty1.dna;
-			let dna_2 = kitty2.dna;
+			ensure!(T::Currency::can_reserve(&who, price), Error::<T>::NotEnoughBalance);
+			Self::hold_deposit(kitty_id, &who, price)?;
 
-			let selector = Self::random_value(&who);
-			let mut new_dna = [0u8; 16];
+			Self::conceive(
+				kitty_id,
+				PendingKitty {
+					owner: who,
+					price,
+					parents: Some((kitty_id1, kitty_id2)),
+					parent_dna: Some((kitty1.dna, kitty2.dna)),
+					generation: kitty1.generation.max(kitty2.generation).saturating_add(1),
+					committed_at: now,
+					reveal_at: now.saturating_add(T::RevealDelay::get()),
+				},
+			);
 
-			for i in 0..new_dna.len() {
-				new_dna[i] = (selector[i] & dna_1[i]) | (!selector[i] & dna_2[i]);
+			// parents rest before breeding again
+			let ready_at = now.saturating_add(T::BreedCooldown::get());
+			for parent_id in [kitty_id1, kitty_id2] {
+				Kitties::<T>::mutate(parent_id, |kitty| {
+					if let Some(kitty) = kitty {
+						kitty.ready_at = ready_at;
+					}
+				});
 			}
 
-			ensure!(T::Currency::can_reserve(&who, price), Error::<T>::NotEnoughBalance);
-			T::Currency::reserve(&who, price)?;
+			Ok(())
+		}
+
+		#[pallet::weight(0)]
+		#[transactional]
+		pub fn buy(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			max_price: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-			Self::create_kitty(kitty_id, new_dna, price, &who);
+			let deposit = Deposits::<T>::get(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
+			let price = Listings::<T>::get(kitty_id).ok_or(Error::<T>::NotListed)?;
+			let seller = Owner::<T>::get(kitty_id).ok_or(Error::<T>::NotOwner)?;
+			ensure!(seller != who, Error::<T>::BuyerIsOwner);
+			ensure!(price <= max_price, Error::<T>::PriceExceedsMaxPrice);
 
-			Self::deposit_event(Event::KittyCreate(who, kitty_id));
+			// the buyer pays the listed price and takes over the kitty deposit
+			ensure!(
+				T::Currency::can_reserve(&who, price.saturating_add(deposit.amount)),
+				Error::<T>::NotEnoughBalance
+			);
+			let (creator, fee, royalty) = Self::sale_cuts(kitty_id, &seller, price);
+			T::Currency::transfer(&who, &Self::account_id(), fee, ExistenceRequirement::KeepAlive)?;
+			// a creator account that cannot take the royalty, e.g. a reaped one, leaves it to the
+			// seller
+			let royalty_paid = creator.map_or(false, |creator| {
+				T::Currency::transfer(&who, &creator, royalty, ExistenceRequirement::KeepAlive)
+					.is_ok()
+			});
+			let royalty = if royalty_paid { royalty } else { Zero::zero() };
+			T::Currency::transfer(
+				&who,
+				&seller,
+				price.saturating_sub(fee).saturating_sub(royalty),
+				ExistenceRequirement::KeepAlive,
+			)?;
+			Self::move_deposit(kitty_id, &who)?;
+
+			Self::change_owner(kitty_id, &seller, &who);
+
+			Self::deposit_event(Event::KittySold(seller, who, kitty_id, price, fee, royalty));
 
 			Ok(())
 		}
 
 		#[pallet::weight(0)]
-		pub fn buy(
+		pub fn list_for_sale(
 			origin: OriginFor<T>,
 			kitty_id: T::KittyIndex,
+			price: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyIndex);
+			ensure!(Owner::<T>::get(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+			ensure!(!KittyAuction::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+
+			Listings::<T>::insert(kitty_id, price);
+
+			Self::deposit_event(Event::KittyListed(who, kitty_id, price));
+
+			Ok(())
+		}
+
+		#[pallet::weight(0)]
+		pub fn burn(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyIndex);
+			ensure!(Owner::<T>::get(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+
+			Self::do_burn(kitty_id, who)
+		}
+
+		/// Let `spender` transfer `kitty_id` once. Approving the owner clears the approval.
+		#[pallet::weight(0)]
+		pub fn approve(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			spender: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyIndex);
+			let owner = Owner::<T>::get(kitty_id).ok_or(Error::<T>::NotOwner)?;
+			ensure!(
+				owner == who || OperatorApprovals::<T>::contains_key(&owner, &who),
+				Error::<T>::NotOwner
+			);
+
+			if spender == owner {
+				Approvals::<T>::remove(kitty_id);
+			} else {
+				Approvals::<T>::insert(kitty_id, &spender);
+			}
+
+			Self::deposit_event(Event::Approval(owner, spender, kitty_id));
+
+			Ok(())
+		}
+
+		/// Let `operator` transfer and approve every kitty of the caller, or stop it from doing so.
+		#[pallet::weight(0)]
+		pub fn set_approval_for_all(
+			origin: OriginFor<T>,
+			operator: T::AccountId,
+			approved: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(operator != who, Error::<T>::ApproveToCaller);
+
+			if approved {
+				OperatorApprovals::<T>::insert(&who, &operator, ());
+			} else {
+				OperatorApprovals::<T>::remove(&who, &operator);
+			}
+
+			Self::deposit_event(Event::ApprovalForAll(who, operator, approved));
+
+			Ok(())
+		}
+
+		#[pallet::weight(0)]
+		pub fn transfer_from(
+			origin: OriginFor<T>,
 			from: T::AccountId,
+			to: T::AccountId,
+			kitty_id: T::KittyIndex,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
-			let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
-			let kitty_host = Owner::<T>::get(kitty_id).ok_or(Error::<T>::NotOwner)?;
-			ensure!(kitty_host == from, Error::<T>::NotOwner);
+			ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyIndex);
+			ensure!(Owner::<T>::get(kitty_id) == Some(from.clone()), Error::<T>::NotOwner);
+			ensure!(
+				who == from ||
+					Approvals::<T>::get(kitty_id) == Some(who.clone()) ||
+					OperatorApprovals::<T>::contains_key(&from, &who),
+				Error::<T>::NotApproved
+			);
 
-			ensure!(T::Currency::can_reserve(&who, kitty.price), Error::<T>::NotEnoughBalance);
-			T::Currency::unreserve(&from, kitty.price);
-			T::Currency::transfer(&from, &who, kitty.price, ExistenceRequirement::KeepAlive)?;
+			Self::do_transfer(kitty_id, from, to)
+		}
 
-			Owner::<T>::insert(kitty_id, Some(who.clone()));
+		/// Reveal a pending kitty to its owner. The DNA only depends on the randomness recorded
+		/// at `reveal_at`, so it is the same whoever reveals it and however late, and anyone may
+		/// do so.
+		#[pallet::weight(0)]
+		pub fn reveal(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let pending = PendingKitties::<T>::get(kitty_id).ok_or(Error::<T>::NotPending)?;
 
-			Self::deposit_event(Event::KittyBuy(from, who, kitty_id));
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now >= pending.reveal_at, Error::<T>::RevealTooEarly);
+
+			let seed = Self::reveal_seed(&pending).ok_or(Error::<T>::RandomnessNotReady)?;
+			let selector = (seed, kitty_id).using_encoded(blake2_128);
+
+			PendingKitties::<T>::remove(kitty_id);
+			Self::release_reveal(pending.reveal_at);
+			Self::create_kitty(
+				kitty_id,
+				Self::inherit_dna(selector, pending.parent_dna),
+				pending.price,
+				pending.parents,
+				pending.generation,
+				&pending.owner,
+			);
+
+			Self::deposit_event(Event::KittyCreate(pending.owner, kitty_id));
+
+			Ok(())
+		}
+
+		#[pallet::weight(0)]
+		pub fn cancel_listing(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Listings::<T>::contains_key(kitty_id), Error::<T>::NotListed);
+			ensure!(Owner::<T>::get(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+
+			Listings::<T>::remove(kitty_id);
+
+			Self::deposit_event(Event::KittyListingCancelled(who, kitty_id));
 
 			Ok(())
 		}
 
 		#[pallet::weight(0)]
-		pub fn sell(
+		pub fn create_auction(
 			origin: OriginFor<T>,
 			kitty_id: T::KittyIndex,
-			dest: T::AccountId,
+			reserve: BalanceOf<T>,
+			end_block: T::BlockNumber,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
-			let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
-			let kitty_host = Owner::<T>::get(kitty_id).ok_or(Error::<T>::NotOwner)?;
-			ensure!(kitty_host == who, Error::<T>::NotOwner);
+			ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyIndex);
+			ensure!(Owner::<T>::get(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+			ensure!(!KittyAuction::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(
+				end_block > <frame_system::Pallet<T>>::block_number(),
+				Error::<T>::InvalidEndBlock
+			);
+
+			let auction_id = Self::auctions_count();
+			ensure!(auction_id != AuctionIndex::MAX, Error::<T>::AuctionsCountOverflow);
 
-			ensure!(T::Currency::can_reserve(&dest, kitty.price), Error::<T>::NotEnoughBalance);
-			T::Currency::unreserve(&who, kitty.price);
-			T::Currency::transfer(&who, &dest, kitty.price, ExistenceRequirement::KeepAlive)?;
+			AuctionsEnding::<T>::try_mutate(end_block, |ending| ending.try_push(auction_id))
+				.map_err(|_| Error::<T>::TooManyAuctions)?;
 
-			Owner::<T>::insert(kitty_id, Some(dest.clone()));
+			let auction =
+				Auction::<T> { kitty_id, seller: who.clone(), reserve, end_block, best_bid: None };
+			Auctions::<T>::insert(auction_id, auction);
+			KittyAuction::<T>::insert(kitty_id, auction_id);
+			AuctionsCount::<T>::put(auction_id + 1);
+			Listings::<T>::remove(kitty_id);
 
-			Self::deposit_event(Event::KittySell(who, dest, kitty_id));
+			Self::deposit_event(Event::AuctionCreated(
+				who, auction_id, kitty_id, reserve, end_block,
+			));
+
+			Ok(())
+		}
+
+		#[pallet::weight(0)]
+		pub fn bid(
+			origin: OriginFor<T>,
+			auction_id: AuctionIndex,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let mut auction =
+				Auctions::<T>::get(auction_id).ok_or(Error::<T>::InvalidAuctionIndex)?;
+			ensure!(
+				<frame_system::Pallet<T>>::block_number() <= auction.end_block,
+				Error::<T>::AuctionEnded
+			);
+			ensure!(auction.seller != who, Error::<T>::BidderIsOwner);
+			if let Some((_, best)) = &auction.best_bid {
+				ensure!(amount > *best, Error::<T>::BidTooLow);
+			}
+			// the winner has to be able to hold the kitty when the auction is settled
+			Self::ensure_can_hold(&who)?;
+
+			// a bid also covers the kitty deposit the winner takes over
+			let deposit =
+				Deposits::<T>::get(auction.kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
+			let reserved = amount.saturating_add(deposit.amount);
+			ensure!(T::Currency::can_reserve(&who, reserved), Error::<T>::NotEnoughBalance);
+			T::Currency::reserve(&who, reserved)?;
+
+			if let Some((bidder, best)) = auction.best_bid.replace((who.clone(), amount)) {
+				T::Currency::unreserve(&bidder, best.saturating_add(deposit.amount));
+			}
+			Auctions::<T>::insert(auction_id, auction);
+
+			Self::deposit_event(Event::AuctionBid(who, auction_id, amount));
+
+			Ok(())
+		}
+
+		/// Drop a pending kitty whose reveal randomness was never recorded and refund its deposit.
+		#[pallet::weight(0)]
+		pub fn cancel_pending(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let pending = PendingKitties::<T>::get(kitty_id).ok_or(Error::<T>::NotPending)?;
+			ensure!(pending.owner == who, Error::<T>::NotOwner);
+			ensure!(
+				<frame_system::Pallet<T>>::block_number() >= pending.reveal_at,
+				Error::<T>::RevealTooEarly
+			);
+			ensure!(Self::reveal_seed(&pending).is_none(), Error::<T>::RandomnessReady);
+
+			PendingKitties::<T>::remove(kitty_id);
+			Self::release_reveal(pending.reveal_at);
+			Self::release_deposit(kitty_id);
+
+			Self::deposit_event(Event::KittyCancelled(who, kitty_id));
 
 			Ok(())
 		}
 	}
 
 	impl<T: Config> Pallet<T> {
-		fn random_value(sender: &T::AccountId) -> [u8; 16] {
+		fn settle_auction(auction_id: AuctionIndex) {
+			let auction = match Auctions::<T>::take(auction_id) {
+				Some(auction) => auction,
+				None => return,
+			};
+			let kitty_id = auction.kitty_id;
+			KittyAuction::<T>::remove(kitty_id);
+
+			let deposit =
+				Deposits::<T>::get(kitty_id).map(|deposit| deposit.amount).unwrap_or_default();
+
+			if let Some((winner, amount)) = auction.best_bid {
+				if amount >= auction.reserve {
+					if let Ok((fee, royalty)) =
+						Self::pay_auction(kitty_id, &auction.seller, &winner, amount)
+					{
+						Self::change_owner(kitty_id, &auction.seller, &winner);
+
+						Self::deposit_event(Event::AuctionSettled(
+							auction.seller,
+							winner,
+							auction_id,
+							kitty_id,
+							amount,
+							fee,
+							royalty,
+						));
+						return
+					}
+				}
+
+				T::Currency::unreserve(&winner, amount.saturating_add(deposit));
+			}
+
+			Self::deposit_event(Event::AuctionFailed(auction.seller, auction_id, kitty_id));
+		}
+
+		/// Pay the winning bid out to the treasury, the creator and the seller and hand the kitty
+		/// deposit over to the winner. Returns the fee and royalty actually paid.
+		#[transactional]
+		fn pay_auction(
+			kitty_id: T::KittyIndex,
+			seller: &T::AccountId,
+			winner: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+			let deposit = Deposits::<T>::get(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
+			Self::take_over_deposit(kitty_id, winner)?;
+			// the winner now holds the deposit in its kitty reserve, so the bid reserve is paid
+			// out in full, the seller getting back the deposit it handed over
+			T::Currency::unreserve(winner, amount.saturating_add(deposit.amount));
+
+			// a cut the treasury or creator cannot take is left to the seller
+			let (creator, fee, royalty) = Self::sale_cuts(kitty_id, seller, amount);
+			let fee = if T::Currency::transfer(
+				winner,
+				&Self::account_id(),
+				fee,
+				ExistenceRequirement::AllowDeath,
+			)
+			.is_ok()
+			{
+				fee
+			} else {
+				Zero::zero()
+			};
+			let royalty_paid = creator.map_or(false, |creator| {
+				T::Currency::transfer(winner, &creator, royalty, ExistenceRequirement::AllowDeath)
+					.is_ok()
+			});
+			let royalty = if royalty_paid { royalty } else { Zero::zero() };
+			T::Currency::transfer(
+				winner,
+				seller,
+				amount
+					.saturating_sub(fee)
+					.saturating_sub(royalty)
+					.saturating_add(deposit.amount),
+				ExistenceRequirement::AllowDeath,
+			)?;
+
+			Ok((fee, royalty))
+		}
+
+		pub(crate) fn random_value(sender: &T::AccountId) -> [u8; 16] {
 			let payload = (
 				T::Randomness::random_seed(),
 				&sender,
@@ -237,16 +802,260 @@ pub mod pallet {
 			payload.using_encoded(blake2_128)
 		}
 
-		fn create_kitty(
+		fn inherit_dna(selector: [u8; 16], parent_dna: Option<([u8; 16], [u8; 16])>) -> [u8; 16] {
+			let (dna_1, dna_2) = match parent_dna {
+				Some(parent_dna) => parent_dna,
+				None => return selector,
+			};
+
+			let mut new_dna = [0u8; 16];
+			for i in 0..new_dna.len() {
+				new_dna[i] = (selector[i] & dna_1[i]) | (!selector[i] & dna_2[i]);
+			}
+			new_dna
+		}
+
+		pub(crate) fn conceive(kitty_id: T::KittyIndex, kitty: PendingKitty<T>) {
+			KittiesCount::<T>::put(kitty_id + 1u32.into());
+
+			let delay = T::RevealDelay::get();
+			if delay.is_zero() {
+				let dna = Self::inherit_dna(Self::random_value(&kitty.owner), kitty.parent_dna);
+				Self::create_kitty(
+					kitty_id,
+					dna,
+					kitty.price,
+					kitty.parents,
+					kitty.generation,
+					&kitty.owner,
+				);
+				Self::deposit_event(Event::KittyCreate(kitty.owner, kitty_id));
+			} else {
+				Self::deposit_event(Event::KittyCommitted(
+					kitty.owner.clone(),
+					kitty_id,
+					kitty.reveal_at,
+				));
+				RevealsDue::<T>::mutate(kitty.reveal_at, |due| *due = due.saturating_add(1));
+				PendingKitties::<T>::insert(kitty_id, kitty);
+			}
+		}
+
+		/// Drop the randomness recorded for `reveal_at` once every kitty due then is revealed.
+		/// The seed `pending` is revealed with. Only randomness that was unknown at commit time
+		/// may decide the dna.
+		fn reveal_seed(pending: &PendingKitty<T>) -> Option<T::Hash> {
+			RevealSeeds::<T>::get(pending.reveal_at)
+				.filter(|(_, known_since)| *known_since > pending.committed_at)
+				.map(|(seed, _)| seed)
+		}
+
+		fn release_reveal(reveal_at: T::BlockNumber) {
+			let due = RevealsDue::<T>::get(reveal_at).saturating_sub(1);
+			if due == 0 {
+				RevealsDue::<T>::remove(reveal_at);
+				RevealSeeds::<T>::remove(reveal_at);
+			} else {
+				RevealsDue::<T>::insert(reveal_at, due);
+			}
+		}
+
+		pub(crate) fn create_kitty(
 			kitty_id: T::KittyIndex,
 			dna: [u8; 16],
 			price: BalanceOf<T>,
+			parents: Option<(T::KittyIndex, T::KittyIndex)>,
+			generation: u32,
 			who: &T::AccountId,
 		) {
-			let kitty = Kitty::<T> { dna, price };
+			let now = <frame_system::Pallet<T>>::block_number();
+			let kitty = Kitty::<T> {
+				dna,
+				price,
+				parents,
+				generation,
+				birth_block: now,
+				traits: KittyTraits::from_dna(&dna),
+				ready_at: now,
+				creator: who.clone(),
+			};
 			Kitties::<T>::insert(kitty_id, kitty);
 			Owner::<T>::insert(kitty_id, Some(who.clone()));
-			KittiesCount::<T>::put(kitty_id + 1u32.into());
+			OwnedKitties::<T>::insert(who, kitty_id, ());
+		}
+
+		pub(crate) fn do_transfer(
+			kitty_id: T::KittyIndex,
+			from: T::AccountId,
+			to: T::AccountId,
+		) -> DispatchResult {
+			let deposit = Deposits::<T>::get(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
+			ensure!(!KittyAuction::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+
+			ensure!(T::Currency::can_reserve(&to, deposit.amount), Error::<T>::NotEnoughBalance);
+			Self::move_deposit(kitty_id, &to)?;
+			Self::change_owner(kitty_id, &from, &to);
+
+			Self::deposit_event(Event::KittyTransfer(from, to, kitty_id));
+
+			Ok(())
+		}
+
+		/// Remove a kitty and release its deposit. `KittiesCount` is left alone, so the id is
+		/// never handed out again.
+		pub(crate) fn do_burn(kitty_id: T::KittyIndex, owner: T::AccountId) -> DispatchResult {
+			ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyIndex);
+			ensure!(!KittyAuction::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+
+			Self::release_deposit(kitty_id);
+			Kitties::<T>::remove(kitty_id);
+			Owner::<T>::remove(kitty_id);
+			OwnedKitties::<T>::remove(&owner, kitty_id);
+			Listings::<T>::remove(kitty_id);
+			Approvals::<T>::remove(kitty_id);
+
+			Self::deposit_event(Event::KittyBurned(owner, kitty_id));
+
+			Ok(())
+		}
+
+		/// The treasury account collecting the marketplace fee.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account()
+		}
+
+		/// Split the marketplace fee and the creator royalty off a sale of `kitty_id`. There is no
+		/// royalty when the creator is the seller. A cut its account cannot take, because it is
+		/// below the existential deposit of an account that does not exist yet, stays with the
+		/// seller.
+		fn sale_cuts(
+			kitty_id: T::KittyIndex,
+			seller: &T::AccountId,
+			price: BalanceOf<T>,
+		) -> (Option<T::AccountId>, BalanceOf<T>, BalanceOf<T>) {
+			let fee = Some(T::MarketplaceFee::get() * price)
+				.filter(|fee| Self::can_receive(&Self::account_id(), *fee))
+				.unwrap_or_else(Zero::zero);
+			let royalty = (T::CreatorRoyalty::get() * price).min(price.saturating_sub(fee));
+			let creator = Kitties::<T>::get(kitty_id)
+				.map(|kitty| kitty.creator)
+				.filter(|c| c != seller && Self::can_receive(c, royalty));
+			let royalty = if creator.is_some() { royalty } else { Zero::zero() };
+			(creator, fee, royalty)
+		}
+
+		/// Whether `amount` can be paid to `who` without being dropped as dust.
+		fn can_receive(who: &T::AccountId, amount: BalanceOf<T>) -> bool {
+			amount.is_zero() ||
+				T::Currency::total_balance(who).saturating_add(amount) >=
+					T::Currency::minimum_balance()
+		}
+
+		/// The named reserve holding the deposit of `kitty_id`. Indices wider than four bytes
+		/// are truncated, which at worst merges two deposits held by the same account; each is
+		/// still released by its own recorded amount.
+		pub fn reserve_id(kitty_id: T::KittyIndex) -> [u8; 8] {
+			let mut id = [0u8; 8];
+			id[..4].copy_from_slice(b"kitt");
+			kitty_id.using_encoded(|index| {
+				let len = index.len().min(4);
+				id[4..4 + len].copy_from_slice(&index[..len]);
+			});
+			id
+		}
+
+		pub(crate) fn hold_deposit(
+			kitty_id: T::KittyIndex,
+			who: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			Self::ensure_can_hold(who)?;
+			T::Currency::reserve_named(&Self::reserve_id(kitty_id), who, amount)?;
+			Deposits::<T>::insert(kitty_id, KittyDeposit { depositor: who.clone(), amount });
+			OwnedKittiesCount::<T>::mutate(who, |count| *count = count.saturating_add(1));
+			Ok(())
+		}
+
+		/// Release the deposit from its depositor and reserve the same amount from `to`.
+		fn move_deposit(kitty_id: T::KittyIndex, to: &T::AccountId) -> DispatchResult {
+			let deposit = Deposits::<T>::get(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
+			let reserve_id = Self::reserve_id(kitty_id);
+
+			if &deposit.depositor != to {
+				Self::ensure_can_hold(to)?;
+			}
+			T::Currency::reserve_named(&reserve_id, to, deposit.amount)?;
+			T::Currency::unreserve_named(&reserve_id, &deposit.depositor, deposit.amount);
+			Self::count_moved_deposit(&deposit.depositor, to);
+			Deposits::<T>::insert(kitty_id, KittyDeposit { depositor: to.clone(), ..deposit });
+
+			Ok(())
+		}
+
+		/// Hand the deposit, and the funds backing it, from its depositor to `to`.
+		#[transactional]
+		fn take_over_deposit(kitty_id: T::KittyIndex, to: &T::AccountId) -> DispatchResult {
+			let deposit = Deposits::<T>::get(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
+
+			if &deposit.depositor != to {
+				Self::ensure_can_hold(to)?;
+			}
+			T::Currency::repatriate_reserved_named(
+				&Self::reserve_id(kitty_id),
+				&deposit.depositor,
+				to,
+				deposit.amount,
+				BalanceStatus::Reserved,
+			)?;
+			Self::count_moved_deposit(&deposit.depositor, to);
+			Deposits::<T>::insert(kitty_id, KittyDeposit { depositor: to.clone(), ..deposit });
+
+			Ok(())
+		}
+
+		fn release_deposit(kitty_id: T::KittyIndex) {
+			if let Some(deposit) = Deposits::<T>::take(kitty_id) {
+				T::Currency::unreserve_named(
+					&Self::reserve_id(kitty_id),
+					&deposit.depositor,
+					deposit.amount,
+				);
+				Self::uncount_deposit(&deposit.depositor);
+			}
+		}
+
+		/// Check that `who` can hold the deposit of another kitty.
+		fn ensure_can_hold(who: &T::AccountId) -> DispatchResult {
+			ensure!(
+				OwnedKittiesCount::<T>::get(who) < T::MaxKittiesOwned::get(),
+				Error::<T>::TooManyKitties
+			);
+			Ok(())
+		}
+
+		fn count_moved_deposit(from: &T::AccountId, to: &T::AccountId) {
+			if from != to {
+				Self::uncount_deposit(from);
+				OwnedKittiesCount::<T>::mutate(to, |count| *count = count.saturating_add(1));
+			}
+		}
+
+		fn uncount_deposit(who: &T::AccountId) {
+			OwnedKittiesCount::<T>::mutate_exists(who, |count| {
+				*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0)
+			});
+		}
+
+		fn change_owner(kitty_id: T::KittyIndex, from: &T::AccountId, to: &T::AccountId) {
+			Owner::<T>::insert(kitty_id, Some(to.clone()));
+			OwnedKitties::<T>::remove(from, kitty_id);
+			OwnedKitties::<T>::insert(to, kitty_id, ());
+			Listings::<T>::remove(kitty_id);
+			Approvals::<T>::remove(kitty_id);
+		}
+
+		pub fn kitties_of(who: &T::AccountId) -> Vec<T::KittyIndex> {
+			OwnedKitties::<T>::iter_prefix(who).map(|(kitty_id, _)| kitty_id).collect()
 		}
 	}
 }
@@ -0,0 +1,259 @@
+//! Storage migrations for the kitties pallet.
+
+use super::*;
+use codec::Encode;
+use frame_support::{
+	storage::migration::{put_storage_value, storage_key_iter},
+	traits::{
+		Get, GetStorageVersion, NamedReservableCurrency, PalletInfoAccess, ReservableCurrency,
+		StorageVersion,
+	},
+	weights::Weight,
+	Blake2_128Concat, StorageHasher,
+};
+use sp_runtime::traits::{One, Saturating, Zero};
+use sp_std::vec::Vec;
+
+const KITTIES: &[u8] = b"Kitties";
+
+fn pallet_prefix<T: Config>() -> &'static [u8] {
+	<Pallet<T>>::name().as_bytes()
+}
+
+fn put_kitty<T: Config, V: Encode>(kitty_id: &T::KittyIndex, value: V) {
+	put_storage_value(
+		pallet_prefix::<T>(),
+		KITTIES,
+		&Blake2_128Concat::hash(&kitty_id.encode()),
+		value,
+	);
+}
+
+pub mod v1 {
+	use super::*;
+
+	/// Add lineage, birth block, traits and a breeding cooldown to every kitty. Lineage was not
+	/// recorded before, so existing kitties become generation 0 founders born at block 0 that
+	/// are ready to breed.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 1 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let old_kitties: Vec<(T::KittyIndex, ([u8; 16], BalanceOf<T>))> =
+			storage_key_iter::<T::KittyIndex, ([u8; 16], BalanceOf<T>), Blake2_128Concat>(
+				pallet_prefix::<T>(),
+				KITTIES,
+			)
+			.collect();
+
+		let count = old_kitties.len() as Weight;
+		for (kitty_id, (dna, price)) in old_kitties {
+			let traits = KittyTraits::from_dna(&dna);
+			let kitty = (
+				dna,
+				price,
+				None::<(T::KittyIndex, T::KittyIndex)>,
+				0u32,
+				T::BlockNumber::zero(),
+				(traits.body, traits.eyes, traits.accessory),
+				T::BlockNumber::zero(),
+			);
+			put_kitty::<T, _>(&kitty_id, kitty);
+		}
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count + 1, count + 1)
+	}
+}
+
+pub mod v2 {
+	use super::*;
+
+	const PENDING_KITTIES: &[u8] = b"PendingKitties";
+
+	/// Move the reserved kitty prices into a named reserve per kitty and record them in
+	/// `Deposits`. A price that can no longer be reserved under its name, e.g. because the
+	/// owner has hit `MaxReserves`, is released and recorded as a zero deposit instead.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 2 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let kitties: Vec<(T::KittyIndex, ([u8; 16], BalanceOf<T>))> =
+			storage_key_iter::<T::KittyIndex, ([u8; 16], BalanceOf<T>), Blake2_128Concat>(
+				pallet_prefix::<T>(),
+				KITTIES,
+			)
+			.collect();
+		let pending: Vec<(T::KittyIndex, (T::AccountId, BalanceOf<T>))> =
+			storage_key_iter::<T::KittyIndex, (T::AccountId, BalanceOf<T>), Blake2_128Concat>(
+				pallet_prefix::<T>(),
+				PENDING_KITTIES,
+			)
+			.collect();
+
+		let mut deposits = Vec::with_capacity(kitties.len() + pending.len());
+		for (kitty_id, (_, price)) in kitties {
+			if let Some(owner) = Owner::<T>::get(kitty_id) {
+				deposits.push((kitty_id, owner, price));
+			}
+		}
+		for (kitty_id, (owner, price)) in pending {
+			deposits.push((kitty_id, owner, price));
+		}
+
+		let count = deposits.len() as Weight;
+		for (kitty_id, owner, price) in deposits {
+			let reserve_id = Pallet::<T>::reserve_id(kitty_id);
+			T::Currency::unreserve(&owner, price);
+			let amount = match T::Currency::reserve_named(&reserve_id, &owner, price) {
+				Ok(()) => price,
+				Err(_) => Zero::zero(),
+			};
+			Deposits::<T>::insert(kitty_id, KittyDeposit { depositor: owner, amount });
+		}
+
+		StorageVersion::new(2).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count * 3 + 1, count * 3 + 1)
+	}
+}
+
+pub mod v3 {
+	use super::*;
+
+	type OldKitty<T> = (
+		[u8; 16],
+		BalanceOf<T>,
+		Option<(<T as Config>::KittyIndex, <T as Config>::KittyIndex)>,
+		u32,
+		<T as frame_system::Config>::BlockNumber,
+		(u8, u8, u8),
+		<T as frame_system::Config>::BlockNumber,
+	);
+
+	/// Record a creator for every kitty. Creators were not tracked before, so the current owner
+	/// takes the royalties from now on.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 3 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let old_kitties: Vec<_> = storage_key_iter::<T::KittyIndex, OldKitty<T>, Blake2_128Concat>(
+			pallet_prefix::<T>(),
+			KITTIES,
+		)
+		.collect();
+
+		let count = old_kitties.len() as Weight;
+		for (kitty_id, kitty) in old_kitties {
+			if let Some(owner) = Owner::<T>::get(kitty_id) {
+				put_kitty::<T, _>(&kitty_id, (kitty, owner));
+			}
+		}
+
+		StorageVersion::new(3).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count * 2 + 1, count + 1)
+	}
+}
+
+pub mod v4 {
+	use super::*;
+
+	/// Build the `OwnedKitties` index from `Owner`. Kitties created before the index existed are
+	/// missing from it.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 4 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let owners: Vec<(T::KittyIndex, T::AccountId)> = Owner::<T>::iter()
+			.filter_map(|(kitty_id, owner)| owner.map(|owner| (kitty_id, owner)))
+			.collect();
+
+		let count = owners.len() as Weight;
+		for (kitty_id, owner) in owners {
+			OwnedKitties::<T>::insert(&owner, kitty_id, ());
+		}
+
+		StorageVersion::new(4).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count + 1, count + 1)
+	}
+}
+
+pub mod v5 {
+	use super::*;
+
+	const PENDING_KITTIES: &[u8] = b"PendingKitties";
+
+	type OldPendingKitty<T> = (
+		<T as frame_system::Config>::AccountId,
+		BalanceOf<T>,
+		Option<(<T as Config>::KittyIndex, <T as Config>::KittyIndex)>,
+		Option<([u8; 16], [u8; 16])>,
+		u32,
+		<T as frame_system::Config>::BlockNumber,
+	);
+
+	/// Record the block whose randomness reveals every pending kitty. Kitties that could already
+	/// be revealed are revealed with the randomness of the block being upgraded, which is
+	/// recorded in its `on_initialize`. The system block number still holds the parent block
+	/// while the upgrade runs.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 5 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let old_pending: Vec<_> = storage_key_iter::<
+			T::KittyIndex,
+			OldPendingKitty<T>,
+			Blake2_128Concat,
+		>(pallet_prefix::<T>(), PENDING_KITTIES)
+		.collect();
+
+		let next = <frame_system::Pallet<T>>::block_number().saturating_add(One::one());
+		let count = old_pending.len() as Weight;
+		for (kitty_id, kitty) in old_pending {
+			let reveal_at = kitty.5.saturating_add(T::RevealDelay::get()).max(next);
+			RevealsDue::<T>::mutate(reveal_at, |due| *due = due.saturating_add(1));
+			put_storage_value(
+				pallet_prefix::<T>(),
+				PENDING_KITTIES,
+				&Blake2_128Concat::hash(&kitty_id.encode()),
+				(kitty, reveal_at),
+			);
+		}
+
+		StorageVersion::new(5).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count * 2 + 1, count * 2 + 1)
+	}
+}
+
+pub mod v6 {
+	use super::*;
+
+	/// Count the kitty deposits each account holds into `OwnedKittiesCount`. Accounts that
+	/// already hold more than `MaxKittiesOwned` keep their kitties, but cannot take on more.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 6 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let depositors: Vec<T::AccountId> =
+			Deposits::<T>::iter_values().map(|deposit| deposit.depositor).collect();
+
+		let count = depositors.len() as Weight;
+		for depositor in depositors {
+			OwnedKittiesCount::<T>::mutate(&depositor, |count| *count = count.saturating_add(1));
+		}
+
+		StorageVersion::new(6).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count * 2 + 1, count + 1)
+	}
+}
@@ -1,11 +1,12 @@
 #![cfg(test)]
 
 use crate as pallet_kitties;
-use frame_support::parameter_types;
+use frame_support::{parameter_types, PalletId};
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
+	Permill,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -57,7 +58,8 @@ impl frame_system::Config for Test {
 }
 
 parameter_types! {
-	pub const ExistentialDeposit: u64 = 1;
+	pub static ExistentialDeposit: u64 = 1;
+	pub const MaxReserves: u32 = 50;
 }
 
 impl pallet_balances::Config for Test {
@@ -67,16 +69,33 @@ impl pallet_balances::Config for Test {
 	type Event = Event;
 	type ExistentialDeposit = ExistentialDeposit;
 	type MaxLocks = ();
-	type MaxReserves = ();
+	type MaxReserves = MaxReserves;
 	type ReserveIdentifier = [u8; 8];
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub const MaxAuctionsPerBlock: u32 = 2;
+	pub const BreedCooldown: u64 = 5;
+	pub static RevealDelay: u64 = 0;
+	pub const KittiesPalletId: PalletId = PalletId(*b"py/kitty");
+	pub static MarketplaceFee: Permill = Permill::zero();
+	pub static CreatorRoyalty: Permill = Permill::zero();
+	pub static MaxKittiesOwned: u32 = 50;
+}
+
 impl pallet_kitties::Config for Test {
 	type Event = Event;
 	type Randomness = RandomnessCollectiveFlip;
 	type Currency = Balances;
 	type KittyIndex = u32;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+	type BreedCooldown = BreedCooldown;
+	type RevealDelay = RevealDelay;
+	type PalletId = KittiesPalletId;
+	type MarketplaceFee = MarketplaceFee;
+	type CreatorRoyalty = CreatorRoyalty;
+	type MaxKittiesOwned = MaxKittiesOwned;
 }
 
 #[macro_export]
@@ -92,7 +111,7 @@ impl pallet_randomness_collective_flip::Config for Test {}
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	pallet_balances::GenesisConfig::<Test> {
-		balances: vec![(1, 10), (2, 10)],
+		balances: vec![(1, 10), (2, 10), (3, 10)],
 		..Default::default()
 	}
 	.assimilate_storage(&mut t)
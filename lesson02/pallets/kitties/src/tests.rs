@@ -1,6 +1,57 @@
 use super::*;
-use crate::mock::{new_test_ext, Event as TestEvent, KittiesModule, Origin, System, Test};
-use frame_support::{assert_noop, assert_ok};
+use crate::mock::{
+	new_test_ext, Balances, CreatorRoyalty, Event as TestEvent, ExistentialDeposit, KittiesModule,
+	MarketplaceFee, MaxKittiesOwned, Origin, RandomnessCollectiveFlip, RevealDelay, System, Test,
+};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::migration::put_storage_value,
+	traits::{
+		tokens::nonfungible::{Inspect, Mutate, Transfer},
+		GetStorageVersion, NamedReservableCurrency, OnFinalize, OnInitialize, OnRuntimeUpgrade,
+		Randomness, ReservableCurrency, StorageVersion,
+	},
+	Blake2_128Concat, StorageHasher,
+};
+use sp_core::H256;
+use sp_runtime::Permill;
+
+fn owned_kitties(who: u64) -> Vec<u32> {
+	let mut kitties = KittiesModule::kitties_of(&who);
+	kitties.sort();
+	kitties
+}
+
+/// Every reserved balance is made up of the kitty deposits an account holds and its
+/// outstanding bids, and every named reserve matches its deposit record.
+fn assert_reserves_match_deposits() {
+	for who in 1..=3u64 {
+		let deposits: u64 = Deposits::<Test>::iter_values()
+			.filter(|deposit| deposit.depositor == who)
+			.map(|deposit| deposit.amount)
+			.sum();
+		let bids: u64 = Auctions::<Test>::iter_values()
+			.filter_map(|auction| match auction.best_bid {
+				Some((bidder, amount)) if bidder == who =>
+					Some(amount + Deposits::<Test>::get(auction.kitty_id).unwrap().amount),
+				_ => None,
+			})
+			.sum();
+		assert_eq!(Balances::reserved_balance(who), deposits + bids);
+
+		let held = Deposits::<Test>::iter_values().filter(|deposit| deposit.depositor == who);
+		assert_eq!(KittiesModule::owned_kitties_count(who), held.count() as u32);
+	}
+
+	for (kitty_id, deposit) in Deposits::<Test>::iter() {
+		let reserve_id = KittiesModule::reserve_id(kitty_id);
+		assert_eq!(
+			Balances::reserved_balance_named(&reserve_id, &deposit.depositor),
+			deposit.amount
+		);
+	}
+}
 
 #[test]
 fn create_works() {
@@ -10,6 +61,12 @@ fn create_works() {
 		let kitty_price = 10;
 		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
 		assert_eq!(Owner::<Test>::get(kitty_id), Some(account_id));
+		assert_eq!(owned_kitties(account_id), vec![kitty_id]);
+
+		let kitty = Kitties::<Test>::get(kitty_id).unwrap();
+		assert_eq!((kitty.parents, kitty.generation, kitty.birth_block), (None, 0, 1));
+		assert_eq!(kitty.traits, KittyTraits::from_dna(&kitty.dna));
+		assert_eq!(kitty.creator, account_id);
 		assert_has_event!(Event::<Test>::KittyCreate(account_id, kitty_id));
 	});
 }
@@ -54,6 +111,8 @@ fn transfer_works() {
 			kitty_id
 		));
 		assert_eq!(Owner::<Test>::get(kitty_id), Some(transfer_account_id));
+		assert_eq!(owned_kitties(account_id), vec![]);
+		assert_eq!(owned_kitties(transfer_account_id), vec![kitty_id]);
 		assert_has_event!(Event::<Test>::KittyTransfer(account_id, transfer_account_id, kitty_id));
 	});
 }
@@ -129,10 +188,60 @@ fn breed_works() {
 			kitty_price
 		));
 		assert_eq!(Owner::<Test>::get(breed_kitty_id), Some(account_id));
+		assert_eq!(
+			owned_kitties(account_id),
+			vec![parent_kitty_id_1, parent_kitty_id_2, breed_kitty_id]
+		);
+
+		let kitty = Kitties::<Test>::get(breed_kitty_id).unwrap();
+		assert_eq!(kitty.parents, Some((parent_kitty_id_1, parent_kitty_id_2)));
+		assert_eq!((kitty.generation, kitty.birth_block, kitty.ready_at), (1, 1, 1));
+		assert_eq!(kitty.traits, KittyTraits::from_dna(&kitty.dna));
+		assert_eq!(Kitties::<Test>::get(parent_kitty_id_1).unwrap().ready_at, 6);
+		assert_eq!(Kitties::<Test>::get(parent_kitty_id_2).unwrap().ready_at, 6);
 		assert_has_event!(Event::<Test>::KittyCreate(account_id, breed_kitty_id));
 	});
 }
 
+#[test]
+fn breed_failed_with_kitty_on_cooldown() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let kitty_price = 2;
+		let parent_kitty_id_1 = 0;
+		let parent_kitty_id_2 = 1;
+		let breed_kitty_id = 2;
+
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_ok!(KittiesModule::breed(
+			Origin::signed(account_id),
+			parent_kitty_id_1,
+			parent_kitty_id_2,
+			kitty_price
+		));
+		assert_noop!(
+			KittiesModule::breed(
+				Origin::signed(account_id),
+				parent_kitty_id_1,
+				breed_kitty_id,
+				kitty_price
+			),
+			Error::<Test>::KittyOnCooldown
+		);
+
+		// after the cooldown the grandchild is one generation further
+		System::set_block_number(6);
+		assert_ok!(KittiesModule::breed(
+			Origin::signed(account_id),
+			parent_kitty_id_1,
+			breed_kitty_id,
+			kitty_price
+		));
+		assert_eq!(Kitties::<Test>::get(3).unwrap().generation, 2);
+	});
+}
+
 #[test]
 fn breed_failed_with_same_parent_index() {
 	new_test_ext().execute_with(|| {
@@ -249,20 +358,164 @@ fn breed_failed_with_not_enough_balance() {
 	});
 }
 
+#[test]
+fn transfer_cancels_listing() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let transfer_account_id = 2;
+		let kitty_price = 2;
+		let kitty_id = 0;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_ok!(KittiesModule::list_for_sale(Origin::signed(account_id), kitty_id, 3));
+		assert_ok!(KittiesModule::transfer(
+			Origin::signed(account_id),
+			transfer_account_id,
+			kitty_id
+		));
+		assert_eq!(Listings::<Test>::get(kitty_id), None);
+	});
+}
+
+#[test]
+fn list_for_sale_works() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		let sale_price = 3;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::list_for_sale(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			sale_price
+		));
+		assert_eq!(Listings::<Test>::get(kitty_id), Some(sale_price));
+		assert_has_event!(Event::<Test>::KittyListed(seller_account_id, kitty_id, sale_price));
+	});
+}
+
+#[test]
+fn list_for_sale_failed_with_invalid_kitty_index() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let invalid_kitty_id = 10;
+		let sale_price = 3;
+		assert_noop!(
+			KittiesModule::list_for_sale(
+				Origin::signed(seller_account_id),
+				invalid_kitty_id,
+				sale_price
+			),
+			Error::<Test>::InvalidKittyIndex
+		);
+	});
+}
+
+#[test]
+fn list_for_sale_failed_with_not_owner() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let invalid_owner_account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		let sale_price = 3;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_noop!(
+			KittiesModule::list_for_sale(
+				Origin::signed(invalid_owner_account_id),
+				kitty_id,
+				sale_price
+			),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
+#[test]
+fn cancel_listing_works() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		let sale_price = 3;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::list_for_sale(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			sale_price
+		));
+		assert_ok!(KittiesModule::cancel_listing(Origin::signed(seller_account_id), kitty_id));
+		assert_eq!(Listings::<Test>::get(kitty_id), None);
+		assert_has_event!(Event::<Test>::KittyListingCancelled(seller_account_id, kitty_id));
+	});
+}
+
+#[test]
+fn cancel_listing_failed_with_not_listed() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_noop!(
+			KittiesModule::cancel_listing(Origin::signed(seller_account_id), kitty_id),
+			Error::<Test>::NotListed
+		);
+	});
+}
+
+#[test]
+fn cancel_listing_failed_with_not_owner() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let invalid_owner_account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		let sale_price = 3;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::list_for_sale(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			sale_price
+		));
+		assert_noop!(
+			KittiesModule::cancel_listing(Origin::signed(invalid_owner_account_id), kitty_id),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
 #[test]
 fn buy_works() {
 	new_test_ext().execute_with(|| {
 		let buyer_account_id = 1;
 		let kitty_id = 0;
 		let kitty_price = 2;
+		let sale_price = 3;
 		let seller_account_id = 2;
 		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
-		assert_ok!(KittiesModule::buy(
-			Origin::signed(buyer_account_id),
+		assert_ok!(KittiesModule::list_for_sale(
+			Origin::signed(seller_account_id),
 			kitty_id,
-			seller_account_id
+			sale_price
+		));
+		assert_ok!(KittiesModule::buy(Origin::signed(buyer_account_id), kitty_id, sale_price));
+		assert_eq!(Owner::<Test>::get(kitty_id), Some(buyer_account_id));
+		assert_eq!(owned_kitties(seller_account_id), vec![]);
+		assert_eq!(owned_kitties(buyer_account_id), vec![kitty_id]);
+		assert_eq!(Listings::<Test>::get(kitty_id), None);
+		assert_eq!(Balances::free_balance(buyer_account_id), 5);
+		assert_eq!(Balances::reserved_balance(buyer_account_id), kitty_price);
+		assert_eq!(Balances::free_balance(seller_account_id), 13);
+		assert_eq!(Balances::reserved_balance(seller_account_id), 0);
+		assert_has_event!(Event::<Test>::KittySold(
+			seller_account_id,
+			buyer_account_id,
+			kitty_id,
+			sale_price,
+			0,
+			0
 		));
-		assert_has_event!(Event::<Test>::KittyBuy(seller_account_id, buyer_account_id, kitty_id));
 	});
 }
 
@@ -271,36 +524,66 @@ fn buy_failed_with_invalid_kitty_index() {
 	new_test_ext().execute_with(|| {
 		let buyer_account_id = 1;
 		let invalid_kitty_id = 10;
+		let sale_price = 3;
+		assert_noop!(
+			KittiesModule::buy(Origin::signed(buyer_account_id), invalid_kitty_id, sale_price),
+			Error::<Test>::InvalidKittyIndex
+		);
+	});
+}
+
+#[test]
+fn buy_failed_with_not_listed() {
+	new_test_ext().execute_with(|| {
+		let buyer_account_id = 1;
+		let kitty_id = 0;
 		let kitty_price = 2;
 		let seller_account_id = 2;
 		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
 		assert_noop!(
-			KittiesModule::buy(
-				Origin::signed(buyer_account_id),
-				invalid_kitty_id,
-				seller_account_id
-			),
-			Error::<Test>::InvalidKittyIndex
+			KittiesModule::buy(Origin::signed(buyer_account_id), kitty_id, kitty_price),
+			Error::<Test>::NotListed
+		);
+	});
+}
+
+#[test]
+fn buy_failed_with_buyer_is_owner() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		let sale_price = 3;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::list_for_sale(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			sale_price
+		));
+		assert_noop!(
+			KittiesModule::buy(Origin::signed(seller_account_id), kitty_id, sale_price),
+			Error::<Test>::BuyerIsOwner
 		);
 	});
 }
 
 #[test]
-fn buy_failed_with_not_owner() {
+fn buy_failed_with_price_exceeds_max_price() {
 	new_test_ext().execute_with(|| {
 		let buyer_account_id = 1;
 		let kitty_id = 0;
 		let kitty_price = 2;
+		let sale_price = 3;
 		let seller_account_id = 2;
-		let invalid_owner_account_id = 100;
 		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::list_for_sale(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			sale_price
+		));
 		assert_noop!(
-			KittiesModule::buy(
-				Origin::signed(buyer_account_id),
-				kitty_id,
-				invalid_owner_account_id
-			),
-			Error::<Test>::NotOwner
+			KittiesModule::buy(Origin::signed(buyer_account_id), kitty_id, sale_price - 1),
+			Error::<Test>::PriceExceedsMaxPrice
 		);
 	});
 }
@@ -309,50 +592,90 @@ fn buy_failed_with_not_owner() {
 fn buy_failed_with_not_enough_balance() {
 	new_test_ext().execute_with(|| {
 		let buyer_account_id = 1;
-		let kitty_id = 1;
+		let kitty_id = 0;
 		let kitty_price = 2;
-		let max_balance = 10;
-		let seller_account_id_1 = 1;
-		let seller_account_id_2 = 2;
-		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id_1), max_balance));
-		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id_2), kitty_price));
+		let sale_price = 9;
+		let seller_account_id = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::list_for_sale(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			sale_price
+		));
 		assert_noop!(
-			KittiesModule::buy(Origin::signed(buyer_account_id), kitty_id, seller_account_id_2),
+			KittiesModule::buy(Origin::signed(buyer_account_id), kitty_id, sale_price),
 			Error::<Test>::NotEnoughBalance
 		);
 	});
 }
 
 #[test]
-fn sell_works() {
+fn create_auction_works() {
 	new_test_ext().execute_with(|| {
-		let buyer_account_id = 1;
+		let seller_account_id = 2;
 		let kitty_id = 0;
 		let kitty_price = 2;
-		let seller_account_id = 2;
+		let reserve = 3;
+		let end_block = 5;
+		let auction_id = 0;
 		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
-		assert_ok!(KittiesModule::sell(
+		assert_ok!(KittiesModule::create_auction(
 			Origin::signed(seller_account_id),
 			kitty_id,
-			buyer_account_id
+			reserve,
+			end_block
+		));
+		assert_eq!(
+			Auctions::<Test>::get(auction_id),
+			Some(Auction {
+				kitty_id,
+				seller: seller_account_id,
+				reserve,
+				end_block,
+				best_bid: None
+			})
+		);
+		assert_eq!(KittyAuction::<Test>::get(kitty_id), Some(auction_id));
+		assert_eq!(AuctionsEnding::<Test>::get(end_block).into_inner(), vec![auction_id]);
+		assert_has_event!(Event::<Test>::AuctionCreated(
+			seller_account_id,
+			auction_id,
+			kitty_id,
+			reserve,
+			end_block
 		));
-		assert_has_event!(Event::<Test>::KittySell(seller_account_id, buyer_account_id, kitty_id));
 	});
 }
 
 #[test]
-fn sell_failed_with_invalid_kitty_index() {
+fn create_auction_cancels_listing() {
 	new_test_ext().execute_with(|| {
-		let buyer_account_id = 1;
-		let invalid_kitty_id = 10;
-		let kitty_price = 2;
 		let seller_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
 		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::list_for_sale(Origin::signed(seller_account_id), kitty_id, 3));
+		assert_ok!(KittiesModule::create_auction(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			3,
+			5
+		));
+		assert_eq!(Listings::<Test>::get(kitty_id), None);
+	});
+}
+
+#[test]
+fn create_auction_failed_with_invalid_kitty_index() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let invalid_kitty_id = 10;
 		assert_noop!(
-			KittiesModule::sell(
+			KittiesModule::create_auction(
 				Origin::signed(seller_account_id),
 				invalid_kitty_id,
-				buyer_account_id
+				3,
+				5
 			),
 			Error::<Test>::InvalidKittyIndex
 		);
@@ -360,40 +683,1497 @@ fn sell_failed_with_invalid_kitty_index() {
 }
 
 #[test]
-fn sell_failed_with_not_owner() {
+fn create_auction_failed_with_not_owner() {
 	new_test_ext().execute_with(|| {
-		let buyer_account_id = 1;
+		let seller_account_id = 2;
+		let invalid_owner_account_id = 1;
 		let kitty_id = 0;
 		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_noop!(
+			KittiesModule::create_auction(Origin::signed(invalid_owner_account_id), kitty_id, 3, 5),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
+#[test]
+fn create_auction_failed_with_kitty_in_auction() {
+	new_test_ext().execute_with(|| {
 		let seller_account_id = 2;
-		let invalid_owner_account_id = 100;
+		let kitty_id = 0;
+		let kitty_price = 2;
 		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::create_auction(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			3,
+			5
+		));
 		assert_noop!(
-			KittiesModule::sell(
-				Origin::signed(invalid_owner_account_id),
+			KittiesModule::create_auction(Origin::signed(seller_account_id), kitty_id, 3, 6),
+			Error::<Test>::KittyInAuction
+		);
+	});
+}
+
+#[test]
+fn create_auction_failed_with_invalid_end_block() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		let current_block = 1;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_noop!(
+			KittiesModule::create_auction(
+				Origin::signed(seller_account_id),
 				kitty_id,
-				buyer_account_id
+				3,
+				current_block
 			),
-			Error::<Test>::NotOwner
+			Error::<Test>::InvalidEndBlock
 		);
 	});
 }
 
 #[test]
-fn sell_failed_with_not_enough_balance() {
+fn create_auction_failed_with_too_many_auctions() {
 	new_test_ext().execute_with(|| {
-		let buyer_account_id = 1;
-		let kitty_id = 1;
-		let kitty_price = 2;
-		let max_balance = 10;
-		let seller_account_id_1 = 1;
-		let seller_account_id_2 = 2;
+		let seller_account_id = 2;
+		let kitty_price = 1;
+		let end_block = 5;
+		for kitty_id in 0..3 {
+			assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+			if kitty_id < 2 {
+				assert_ok!(KittiesModule::create_auction(
+					Origin::signed(seller_account_id),
+					kitty_id,
+					3,
+					end_block
+				));
+			}
+		}
+		assert_noop!(
+			KittiesModule::create_auction(Origin::signed(seller_account_id), 2, 3, end_block),
+			Error::<Test>::TooManyAuctions
+		);
+	});
+}
 
-		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id_1), max_balance));
-		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id_2), kitty_price));
+#[test]
+fn transfer_failed_with_kitty_in_auction() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let transfer_account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::create_auction(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			3,
+			5
+		));
 		assert_noop!(
-			KittiesModule::sell(Origin::signed(seller_account_id_2), kitty_id, buyer_account_id),
-			Error::<Test>::NotEnoughBalance
+			KittiesModule::transfer(
+				Origin::signed(seller_account_id),
+				transfer_account_id,
+				kitty_id
+			),
+			Error::<Test>::KittyInAuction
 		);
+		assert_noop!(
+			KittiesModule::list_for_sale(Origin::signed(seller_account_id), kitty_id, 3),
+			Error::<Test>::KittyInAuction
+		);
+	});
+}
+
+#[test]
+fn bid_works() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let bidder_account_id_1 = 1;
+		let bidder_account_id_2 = 3;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		let auction_id = 0;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::create_auction(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			3,
+			5
+		));
+
+		assert_ok!(KittiesModule::bid(Origin::signed(bidder_account_id_1), auction_id, 3));
+		assert_eq!(Balances::reserved_balance(bidder_account_id_1), 3 + kitty_price);
+		assert_has_event!(Event::<Test>::AuctionBid(bidder_account_id_1, auction_id, 3));
+
+		// outbidding returns the previous bid
+		assert_ok!(KittiesModule::bid(Origin::signed(bidder_account_id_2), auction_id, 4));
+		assert_eq!(Balances::reserved_balance(bidder_account_id_1), 0);
+		assert_eq!(Balances::reserved_balance(bidder_account_id_2), 4 + kitty_price);
+		assert_eq!(
+			Auctions::<Test>::get(auction_id).unwrap().best_bid,
+			Some((bidder_account_id_2, 4))
+		);
+		assert_has_event!(Event::<Test>::AuctionBid(bidder_account_id_2, auction_id, 4));
+	});
+}
+
+#[test]
+fn bid_failed_with_invalid_auction_index() {
+	new_test_ext().execute_with(|| {
+		let bidder_account_id = 1;
+		let invalid_auction_id = 10;
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(bidder_account_id), invalid_auction_id, 3),
+			Error::<Test>::InvalidAuctionIndex
+		);
+	});
+}
+
+#[test]
+fn bid_failed_with_auction_ended() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let bidder_account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		let auction_id = 0;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::create_auction(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			3,
+			5
+		));
+		System::set_block_number(6);
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(bidder_account_id), auction_id, 3),
+			Error::<Test>::AuctionEnded
+		);
+	});
+}
+
+#[test]
+fn bid_failed_with_bidder_is_owner() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		let auction_id = 0;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::create_auction(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			3,
+			5
+		));
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(seller_account_id), auction_id, 3),
+			Error::<Test>::BidderIsOwner
+		);
+	});
+}
+
+#[test]
+fn bid_failed_with_bid_too_low() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let bidder_account_id_1 = 1;
+		let bidder_account_id_2 = 3;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		let auction_id = 0;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::create_auction(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			3,
+			5
+		));
+		assert_ok!(KittiesModule::bid(Origin::signed(bidder_account_id_1), auction_id, 4));
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(bidder_account_id_2), auction_id, 4),
+			Error::<Test>::BidTooLow
+		);
+	});
+}
+
+#[test]
+fn bid_failed_with_not_enough_balance() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let bidder_account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		let auction_id = 0;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::create_auction(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			3,
+			5
+		));
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(bidder_account_id), auction_id, 9),
+			Error::<Test>::NotEnoughBalance
+		);
+	});
+}
+
+#[test]
+fn auction_settles_to_highest_bidder() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let bidder_account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		let auction_id = 0;
+		let end_block = 5;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::create_auction(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			3,
+			end_block
+		));
+		assert_ok!(KittiesModule::bid(Origin::signed(bidder_account_id), auction_id, 4));
+
+		KittiesModule::on_finalize(end_block);
+
+		assert_eq!(Owner::<Test>::get(kitty_id), Some(bidder_account_id));
+		assert_eq!(owned_kitties(seller_account_id), vec![]);
+		assert_eq!(owned_kitties(bidder_account_id), vec![kitty_id]);
+		assert_eq!(Balances::free_balance(bidder_account_id), 4);
+		assert_eq!(Balances::reserved_balance(bidder_account_id), kitty_price);
+		assert_eq!(Balances::free_balance(seller_account_id), 14);
+		assert_eq!(Balances::reserved_balance(seller_account_id), 0);
+		assert_eq!(Auctions::<Test>::get(auction_id), None);
+		assert_eq!(KittyAuction::<Test>::get(kitty_id), None);
+		assert!(AuctionsEnding::<Test>::get(end_block).is_empty());
+		assert_has_event!(Event::<Test>::AuctionSettled(
+			seller_account_id,
+			bidder_account_id,
+			auction_id,
+			kitty_id,
+			4,
+			0,
+			0
+		));
+	});
+}
+
+#[test]
+fn auction_returns_kitty_when_reserve_not_met() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let bidder_account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		let auction_id = 0;
+		let end_block = 5;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::create_auction(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			3,
+			end_block
+		));
+		assert_ok!(KittiesModule::bid(Origin::signed(bidder_account_id), auction_id, 2));
+
+		KittiesModule::on_finalize(end_block);
+
+		assert_eq!(Owner::<Test>::get(kitty_id), Some(seller_account_id));
+		assert_eq!(owned_kitties(seller_account_id), vec![kitty_id]);
+		assert_eq!(owned_kitties(bidder_account_id), vec![]);
+		assert_eq!(Balances::free_balance(bidder_account_id), 10);
+		assert_eq!(Balances::reserved_balance(bidder_account_id), 0);
+		assert_eq!(Balances::reserved_balance(seller_account_id), kitty_price);
+		assert_eq!(KittyAuction::<Test>::get(kitty_id), None);
+		assert_has_event!(Event::<Test>::AuctionFailed(seller_account_id, auction_id, kitty_id));
+
+		// the kitty can be traded again
+		assert_ok!(KittiesModule::list_for_sale(Origin::signed(seller_account_id), kitty_id, 3));
+	});
+}
+
+#[test]
+fn create_commits_when_reveal_delay_is_set() {
+	new_test_ext().execute_with(|| {
+		RevealDelay::set(&100);
+
+		let account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_eq!(Kitties::<Test>::get(kitty_id), None);
+		assert_eq!(Owner::<Test>::get(kitty_id), None);
+		assert_eq!(KittiesModule::kitties_count(), Some(1));
+		assert_eq!(Balances::reserved_balance(account_id), kitty_price);
+
+		let pending = PendingKitties::<Test>::get(kitty_id).unwrap();
+		assert_eq!((pending.owner, pending.committed_at, pending.reveal_at), (account_id, 1, 101));
+		assert_eq!(RevealsDue::<Test>::get(101), 1);
+		assert_has_event!(Event::<Test>::KittyCommitted(account_id, kitty_id, 101));
+	});
+}
+
+#[test]
+fn reveal_works() {
+	new_test_ext().execute_with(|| {
+		RevealDelay::set(&100);
+
+		let account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+
+		System::set_block_number(101);
+		KittiesModule::on_initialize(101);
+		assert!(RevealSeeds::<Test>::contains_key(101));
+		assert_ok!(KittiesModule::reveal(Origin::signed(account_id), kitty_id));
+		assert_eq!(PendingKitties::<Test>::get(kitty_id), None);
+		assert!(!RevealsDue::<Test>::contains_key(101));
+		assert!(!RevealSeeds::<Test>::contains_key(101));
+		assert_eq!(Owner::<Test>::get(kitty_id), Some(account_id));
+		assert_eq!(owned_kitties(account_id), vec![kitty_id]);
+		assert_eq!(Kitties::<Test>::get(kitty_id).unwrap().birth_block, 101);
+		assert_has_event!(Event::<Test>::KittyCreate(account_id, kitty_id));
+	});
+}
+
+#[test]
+fn reveal_keeps_lineage_of_bred_kitty() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let kitty_price = 2;
+		let parent_kitty_id_1 = 0;
+		let parent_kitty_id_2 = 1;
+		let breed_kitty_id = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+
+		RevealDelay::set(&100);
+		assert_ok!(KittiesModule::breed(
+			Origin::signed(account_id),
+			parent_kitty_id_1,
+			parent_kitty_id_2,
+			kitty_price
+		));
+		assert_has_event!(Event::<Test>::KittyCommitted(account_id, breed_kitty_id, 101));
+
+		System::set_block_number(101);
+		KittiesModule::on_initialize(101);
+		assert_ok!(KittiesModule::reveal(Origin::signed(account_id), breed_kitty_id));
+
+		let kitty = Kitties::<Test>::get(breed_kitty_id).unwrap();
+		let dna_1 = Kitties::<Test>::get(parent_kitty_id_1).unwrap().dna;
+		let dna_2 = Kitties::<Test>::get(parent_kitty_id_2).unwrap().dna;
+		assert_eq!(kitty.parents, Some((parent_kitty_id_1, parent_kitty_id_2)));
+		assert_eq!(kitty.generation, 1);
+		// every gene comes from one of the parents
+		for i in 0..kitty.dna.len() {
+			assert_eq!(kitty.dna[i] & !(dna_1[i] | dna_2[i]), 0);
+		}
+	});
+}
+
+#[test]
+fn reveal_failed_with_not_pending() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_noop!(
+			KittiesModule::reveal(Origin::signed(account_id), kitty_id),
+			Error::<Test>::NotPending
+		);
+	});
+}
+
+#[test]
+fn reveal_by_anyone_gives_kitty_to_owner() {
+	new_test_ext().execute_with(|| {
+		RevealDelay::set(&100);
+
+		let account_id = 1;
+		let other_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		System::set_block_number(101);
+		KittiesModule::on_initialize(101);
+		assert_ok!(KittiesModule::reveal(Origin::signed(other_account_id), kitty_id));
+		assert_eq!(Owner::<Test>::get(kitty_id), Some(account_id));
+		assert_eq!(Kitties::<Test>::get(kitty_id).unwrap().creator, account_id);
+		assert_has_event!(Event::<Test>::KittyCreate(account_id, kitty_id));
+	});
+}
+
+#[test]
+fn cancel_pending_refunds_kitty_that_cannot_be_revealed() {
+	new_test_ext().execute_with(|| {
+		RevealDelay::set(&100);
+
+		let account_id = 1;
+		let other_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_eq!(Balances::reserved_balance(account_id), kitty_price);
+		assert_noop!(
+			KittiesModule::cancel_pending(Origin::signed(account_id), kitty_id),
+			Error::<Test>::RevealTooEarly
+		);
+
+		// the randomness of the reveal block is never recorded
+		System::set_block_number(101);
+		assert_noop!(
+			KittiesModule::reveal(Origin::signed(account_id), kitty_id),
+			Error::<Test>::RandomnessNotReady
+		);
+		assert_noop!(
+			KittiesModule::cancel_pending(Origin::signed(other_account_id), kitty_id),
+			Error::<Test>::NotOwner
+		);
+		assert_ok!(KittiesModule::cancel_pending(Origin::signed(account_id), kitty_id));
+
+		assert_eq!(PendingKitties::<Test>::get(kitty_id), None);
+		assert_eq!(RevealsDue::<Test>::get(101), 0);
+		assert_eq!(Balances::reserved_balance(account_id), 0);
+		assert_eq!(KittiesModule::owned_kitties_count(account_id), 0);
+		assert_has_event!(Event::<Test>::KittyCancelled(account_id, kitty_id));
+		assert_noop!(
+			KittiesModule::reveal(Origin::signed(account_id), kitty_id),
+			Error::<Test>::NotPending
+		);
+	});
+}
+
+#[test]
+fn cancel_pending_fails_once_kitty_can_be_revealed() {
+	new_test_ext().execute_with(|| {
+		RevealDelay::set(&100);
+
+		let account_id = 1;
+		let kitty_id = 0;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), 2));
+		System::set_block_number(101);
+		KittiesModule::on_initialize(101);
+
+		assert_noop!(
+			KittiesModule::cancel_pending(Origin::signed(account_id), kitty_id),
+			Error::<Test>::RandomnessReady
+		);
+	});
+}
+
+/// Commit to a kitty at block 1, advance to `reveal_block` with fresh randomness in every
+/// block, and reveal it. Returns the DNA and the randomness at the time of the reveal.
+fn reveal_dna_at(reveal_block: u64) -> ([u8; 16], H256) {
+	new_test_ext().execute_with(|| {
+		RevealDelay::set(&100);
+		assert_ok!(KittiesModule::create(Origin::signed(1), 2));
+
+		for block in 2..=reveal_block {
+			System::set_block_number(block);
+			System::set_parent_hash(H256::repeat_byte(block as u8));
+			RandomnessCollectiveFlip::on_initialize(block);
+			KittiesModule::on_initialize(block);
+		}
+		assert_ok!(KittiesModule::reveal(Origin::signed(2), 0));
+
+		(Kitties::<Test>::get(0).unwrap().dna, RandomnessCollectiveFlip::random(b"kitty").0)
+	})
+}
+
+#[test]
+fn reveal_in_later_block_gives_same_dna() {
+	let (dna, randomness) = reveal_dna_at(101);
+	let (later_dna, later_randomness) = reveal_dna_at(150);
+
+	assert_ne!(randomness, later_randomness);
+	assert_eq!(dna, later_dna);
+}
+
+#[test]
+fn reveal_failed_with_reveal_too_early() {
+	new_test_ext().execute_with(|| {
+		RevealDelay::set(&100);
+
+		let account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		System::set_block_number(100);
+		assert_noop!(
+			KittiesModule::reveal(Origin::signed(account_id), kitty_id),
+			Error::<Test>::RevealTooEarly
+		);
+	});
+}
+
+#[test]
+fn reveal_failed_with_randomness_not_ready() {
+	new_test_ext().execute_with(|| {
+		// the mock randomness only vouches for material from 81 blocks back
+		RevealDelay::set(&3);
+
+		let account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		System::set_block_number(4);
+		KittiesModule::on_initialize(4);
+		assert_noop!(
+			KittiesModule::reveal(Origin::signed(account_id), kitty_id),
+			Error::<Test>::RandomnessNotReady
+		);
+	});
+}
+
+#[test]
+fn approve_works() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let spender_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_ok!(KittiesModule::approve(
+			Origin::signed(account_id),
+			kitty_id,
+			spender_account_id
+		));
+		assert_eq!(Approvals::<Test>::get(kitty_id), Some(spender_account_id));
+		assert_has_event!(Event::<Test>::Approval(account_id, spender_account_id, kitty_id));
+
+		// approving the owner clears the approval
+		assert_ok!(KittiesModule::approve(Origin::signed(account_id), kitty_id, account_id));
+		assert_eq!(Approvals::<Test>::get(kitty_id), None);
+	});
+}
+
+#[test]
+fn approve_works_for_operator() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let operator_account_id = 2;
+		let spender_account_id = 3;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_ok!(KittiesModule::set_approval_for_all(
+			Origin::signed(account_id),
+			operator_account_id,
+			true
+		));
+		assert_ok!(KittiesModule::approve(
+			Origin::signed(operator_account_id),
+			kitty_id,
+			spender_account_id
+		));
+		assert_eq!(Approvals::<Test>::get(kitty_id), Some(spender_account_id));
+		assert_has_event!(Event::<Test>::Approval(account_id, spender_account_id, kitty_id));
+	});
+}
+
+#[test]
+fn approve_failed_with_invalid_kitty_index() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let spender_account_id = 2;
+		let invalid_kitty_id = 0;
+		assert_noop!(
+			KittiesModule::approve(
+				Origin::signed(account_id),
+				invalid_kitty_id,
+				spender_account_id
+			),
+			Error::<Test>::InvalidKittyIndex
+		);
+	});
+}
+
+#[test]
+fn approve_failed_with_not_owner() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let invalid_owner_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_noop!(
+			KittiesModule::approve(
+				Origin::signed(invalid_owner_account_id),
+				kitty_id,
+				invalid_owner_account_id
+			),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
+#[test]
+fn set_approval_for_all_works() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let operator_account_id = 2;
+		assert_ok!(KittiesModule::set_approval_for_all(
+			Origin::signed(account_id),
+			operator_account_id,
+			true
+		));
+		assert!(OperatorApprovals::<Test>::contains_key(account_id, operator_account_id));
+		assert_has_event!(Event::<Test>::ApprovalForAll(account_id, operator_account_id, true));
+
+		assert_ok!(KittiesModule::set_approval_for_all(
+			Origin::signed(account_id),
+			operator_account_id,
+			false
+		));
+		assert!(!OperatorApprovals::<Test>::contains_key(account_id, operator_account_id));
+		assert_has_event!(Event::<Test>::ApprovalForAll(account_id, operator_account_id, false));
+	});
+}
+
+#[test]
+fn set_approval_for_all_failed_with_approve_to_caller() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		assert_noop!(
+			KittiesModule::set_approval_for_all(Origin::signed(account_id), account_id, true),
+			Error::<Test>::ApproveToCaller
+		);
+	});
+}
+
+#[test]
+fn transfer_from_works_for_approved_spender() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let spender_account_id = 2;
+		let transfer_account_id = 3;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_ok!(KittiesModule::approve(
+			Origin::signed(account_id),
+			kitty_id,
+			spender_account_id
+		));
+		assert_ok!(KittiesModule::transfer_from(
+			Origin::signed(spender_account_id),
+			account_id,
+			transfer_account_id,
+			kitty_id
+		));
+		assert_eq!(Owner::<Test>::get(kitty_id), Some(transfer_account_id));
+		assert_eq!(Approvals::<Test>::get(kitty_id), None);
+		assert_eq!(Balances::reserved_balance(transfer_account_id), kitty_price);
+		assert_has_event!(Event::<Test>::KittyTransfer(account_id, transfer_account_id, kitty_id));
+	});
+}
+
+#[test]
+fn transfer_from_works_for_operator() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let operator_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_ok!(KittiesModule::set_approval_for_all(
+			Origin::signed(account_id),
+			operator_account_id,
+			true
+		));
+		assert_ok!(KittiesModule::transfer_from(
+			Origin::signed(operator_account_id),
+			account_id,
+			operator_account_id,
+			kitty_id
+		));
+		assert_eq!(Owner::<Test>::get(kitty_id), Some(operator_account_id));
+	});
+}
+
+#[test]
+fn transfer_from_failed_with_not_approved() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let spender_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_noop!(
+			KittiesModule::transfer_from(
+				Origin::signed(spender_account_id),
+				account_id,
+				spender_account_id,
+				kitty_id
+			),
+			Error::<Test>::NotApproved
+		);
+	});
+}
+
+#[test]
+fn transfer_from_failed_with_not_owner() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let spender_account_id = 2;
+		let invalid_owner_account_id = 3;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_ok!(KittiesModule::approve(
+			Origin::signed(account_id),
+			kitty_id,
+			spender_account_id
+		));
+		assert_noop!(
+			KittiesModule::transfer_from(
+				Origin::signed(spender_account_id),
+				invalid_owner_account_id,
+				spender_account_id,
+				kitty_id
+			),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
+#[test]
+fn ownership_change_clears_approval() {
+	new_test_ext().execute_with(|| {
+		let seller_account_id = 2;
+		let buyer_account_id = 1;
+		let spender_account_id = 3;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), kitty_price));
+		assert_ok!(KittiesModule::approve(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			spender_account_id
+		));
+		assert_ok!(KittiesModule::list_for_sale(Origin::signed(seller_account_id), kitty_id, 3));
+		assert_ok!(KittiesModule::buy(Origin::signed(buyer_account_id), kitty_id, 3));
+		assert_eq!(Approvals::<Test>::get(kitty_id), None);
+		assert_noop!(
+			KittiesModule::transfer_from(
+				Origin::signed(spender_account_id),
+				buyer_account_id,
+				spender_account_id,
+				kitty_id
+			),
+			Error::<Test>::NotApproved
+		);
+	});
+}
+
+#[test]
+fn burn_works() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_ok!(KittiesModule::list_for_sale(Origin::signed(account_id), kitty_id, 3));
+		assert_ok!(KittiesModule::burn(Origin::signed(account_id), kitty_id));
+		assert_eq!(Kitties::<Test>::get(kitty_id), None);
+		assert_eq!(Owner::<Test>::get(kitty_id), None);
+		assert_eq!(Listings::<Test>::get(kitty_id), None);
+		assert_eq!(owned_kitties(account_id), vec![]);
+		assert_eq!(Balances::reserved_balance(account_id), 0);
+		assert_eq!(Balances::free_balance(account_id), 10);
+		assert_has_event!(Event::<Test>::KittyBurned(account_id, kitty_id));
+
+		// burned ids are not reused
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_eq!(owned_kitties(account_id), vec![kitty_id + 1]);
+	});
+}
+
+#[test]
+fn burn_failed_with_invalid_kitty_index() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_ok!(KittiesModule::burn(Origin::signed(account_id), kitty_id));
+		assert_noop!(
+			KittiesModule::burn(Origin::signed(account_id), kitty_id),
+			Error::<Test>::InvalidKittyIndex
+		);
+	});
+}
+
+#[test]
+fn burn_failed_with_not_owner() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let invalid_owner_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_noop!(
+			KittiesModule::burn(Origin::signed(invalid_owner_account_id), kitty_id),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
+#[test]
+fn burn_failed_with_kitty_in_auction() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_ok!(KittiesModule::create_auction(Origin::signed(account_id), kitty_id, 3, 5));
+		assert_noop!(
+			KittiesModule::burn(Origin::signed(account_id), kitty_id),
+			Error::<Test>::KittyInAuction
+		);
+	});
+}
+
+#[test]
+fn nonfungible_inspect_works() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let kitty_id = 0;
+		let invalid_kitty_id = 1;
+		let kitty_price: u64 = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+
+		let dna = Kitties::<Test>::get(kitty_id).unwrap().dna;
+		assert_eq!(<KittiesModule as Inspect<u64>>::owner(&kitty_id), Some(account_id));
+		assert_eq!(<KittiesModule as Inspect<u64>>::owner(&invalid_kitty_id), None);
+		assert_eq!(<KittiesModule as Inspect<u64>>::typed_attribute(&kitty_id, b"dna"), Some(dna));
+		assert_eq!(
+			<KittiesModule as Inspect<u64>>::typed_attribute(&kitty_id, b"price"),
+			Some(kitty_price)
+		);
+		assert_eq!(<KittiesModule as Inspect<u64>>::attribute(&kitty_id, b"eyes"), None);
+		assert!(<KittiesModule as Inspect<u64>>::can_transfer(&kitty_id));
+		assert!(!<KittiesModule as Inspect<u64>>::can_transfer(&invalid_kitty_id));
+
+		assert_ok!(KittiesModule::create_auction(Origin::signed(account_id), kitty_id, 3, 5));
+		assert!(!<KittiesModule as Inspect<u64>>::can_transfer(&kitty_id));
+	});
+}
+
+#[test]
+fn nonfungible_transfer_works() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let transfer_account_id = 2;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_ok!(<KittiesModule as Transfer<u64>>::transfer(&kitty_id, &transfer_account_id));
+		assert_eq!(Owner::<Test>::get(kitty_id), Some(transfer_account_id));
+		assert_eq!(owned_kitties(transfer_account_id), vec![kitty_id]);
+		assert_eq!(Balances::reserved_balance(account_id), 0);
+		assert_eq!(Balances::reserved_balance(transfer_account_id), kitty_price);
+		assert_has_event!(Event::<Test>::KittyTransfer(account_id, transfer_account_id, kitty_id));
+	});
+}
+
+#[test]
+fn nonfungible_transfer_failed_with_invalid_kitty_index() {
+	new_test_ext().execute_with(|| {
+		let transfer_account_id = 2;
+		let invalid_kitty_id = 0;
+		assert_noop!(
+			<KittiesModule as Transfer<u64>>::transfer(&invalid_kitty_id, &transfer_account_id),
+			Error::<Test>::InvalidKittyIndex
+		);
+	});
+}
+
+#[test]
+fn nonfungible_mint_into_works() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let kitty_id = 0;
+		assert_ok!(<KittiesModule as Mutate<u64>>::mint_into(&kitty_id, &account_id));
+		assert_eq!(Owner::<Test>::get(kitty_id), Some(account_id));
+		assert_eq!(KittiesModule::kitties_count(), Some(1));
+		assert_eq!(Balances::reserved_balance(account_id), 0);
+		assert_has_event!(Event::<Test>::KittyCreate(account_id, kitty_id));
+	});
+}
+
+#[test]
+fn nonfungible_mint_into_commits_when_reveal_delay_is_set() {
+	new_test_ext().execute_with(|| {
+		RevealDelay::set(&100);
+
+		let account_id = 1;
+		let kitty_id = 0;
+		assert_ok!(<KittiesModule as Mutate<u64>>::mint_into(&kitty_id, &account_id));
+		assert_eq!(Owner::<Test>::get(kitty_id), None);
+		assert_eq!(KittiesModule::kitties_count(), Some(1));
+		assert_eq!(PendingKitties::<Test>::get(kitty_id).unwrap().reveal_at, 101);
+		assert_has_event!(Event::<Test>::KittyCommitted(account_id, kitty_id, 101));
+
+		System::set_block_number(101);
+		KittiesModule::on_initialize(101);
+		assert_ok!(KittiesModule::reveal(Origin::signed(account_id), kitty_id));
+		assert_eq!(Owner::<Test>::get(kitty_id), Some(account_id));
+	});
+}
+
+#[test]
+fn nonfungible_burn_from_works() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+		assert_ok!(<KittiesModule as Mutate<u64>>::burn_from(&kitty_id));
+		assert_eq!(<KittiesModule as Inspect<u64>>::owner(&kitty_id), None);
+		assert_eq!(Balances::reserved_balance(account_id), 0);
+		assert_has_event!(Event::<Test>::KittyBurned(account_id, kitty_id));
+	});
+}
+
+#[test]
+fn nonfungible_mint_into_failed_with_invalid_kitty_index() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let used_kitty_id = 0;
+		let skipped_kitty_id = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), 2));
+		assert_noop!(
+			<KittiesModule as Mutate<u64>>::mint_into(&used_kitty_id, &account_id),
+			Error::<Test>::InvalidKittyIndex
+		);
+		assert_noop!(
+			<KittiesModule as Mutate<u64>>::mint_into(&skipped_kitty_id, &account_id),
+			Error::<Test>::InvalidKittyIndex
+		);
+	});
+}
+
+#[test]
+fn migrate_backfills_lineage_and_traits() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<KittiesModule>();
+
+		let kitty_id: u32 = 0;
+		let dna = [7u8; 16];
+		let old_kitty: ([u8; 16], u64) = (dna, 2);
+		put_storage_value(
+			b"KittiesModule",
+			b"Kitties",
+			&Blake2_128Concat::hash(&kitty_id.encode()),
+			old_kitty,
+		);
+
+		crate::migrations::v1::migrate::<Test>();
+
+		let kitty = Kitties::<Test>::get(kitty_id).unwrap();
+		assert_eq!((kitty.dna, kitty.price), (dna, 2));
+		assert_eq!((kitty.parents, kitty.generation), (None, 0));
+		assert_eq!((kitty.birth_block, kitty.ready_at), (0, 0));
+		assert_eq!(kitty.traits, KittyTraits { body: 7, eyes: 7, accessory: 7 });
+		assert_eq!(KittiesModule::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn migrate_moves_reserved_prices_into_deposits() {
+	new_test_ext().execute_with(|| {
+		let account_id = 1;
+		let kitty_id = 0;
+		let kitty_price = 3;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), kitty_price));
+
+		// roll back to an anonymous reserve without a deposit record
+		let reserve_id = KittiesModule::reserve_id(kitty_id);
+		Balances::unreserve_named(&reserve_id, &account_id, kitty_price);
+		assert_ok!(Balances::reserve(&account_id, kitty_price));
+		Deposits::<Test>::remove(kitty_id);
+		StorageVersion::new(1).put::<KittiesModule>();
+
+		crate::migrations::v2::migrate::<Test>();
+
+		assert_eq!(
+			Deposits::<Test>::get(kitty_id),
+			Some(KittyDeposit { depositor: account_id, amount: kitty_price })
+		);
+		assert_eq!(Balances::reserved_balance_named(&reserve_id, &account_id), kitty_price);
+		assert_eq!(Balances::reserved_balance(account_id), kitty_price);
+		assert_eq!(KittiesModule::on_chain_storage_version(), 2);
+	});
+}
+
+#[test]
+fn deposit_follows_kitty() {
+	new_test_ext().execute_with(|| {
+		let (account_id_1, account_id_2, account_id_3) = (1, 2, 3);
+		let kitty_id = 0;
+		let kitty_price = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id_1), kitty_price));
+		assert_reserves_match_deposits();
+
+		assert_ok!(KittiesModule::transfer(Origin::signed(account_id_1), account_id_2, kitty_id));
+		assert_eq!(KittiesModule::deposits(kitty_id).unwrap().depositor, account_id_2);
+		assert_reserves_match_deposits();
+
+		assert_ok!(KittiesModule::list_for_sale(Origin::signed(account_id_2), kitty_id, 1));
+		assert_ok!(KittiesModule::buy(Origin::signed(account_id_3), kitty_id, 1));
+		assert_eq!(KittiesModule::deposits(kitty_id).unwrap().depositor, account_id_3);
+		assert_reserves_match_deposits();
+
+		assert_ok!(KittiesModule::create_auction(Origin::signed(account_id_3), kitty_id, 1, 3));
+		assert_ok!(KittiesModule::bid(Origin::signed(account_id_1), 0, 1));
+		assert_reserves_match_deposits();
+		assert_ok!(KittiesModule::bid(Origin::signed(account_id_2), 0, 2));
+		assert_reserves_match_deposits();
+
+		KittiesModule::on_finalize(3);
+		assert_eq!(KittiesModule::deposits(kitty_id).unwrap().depositor, account_id_2);
+		assert_reserves_match_deposits();
+
+		assert_ok!(KittiesModule::burn(Origin::signed(account_id_2), kitty_id));
+		assert_eq!(KittiesModule::deposits(kitty_id), None);
+		assert_reserves_match_deposits();
+		for who in [account_id_1, account_id_2, account_id_3] {
+			assert_eq!(Balances::reserved_balance(who), 0);
+		}
+	});
+}
+
+#[test]
+fn reserves_match_deposits_after_random_calls() {
+	new_test_ext().execute_with(|| {
+		let mut seed: u64 = 42;
+		let mut next = |bound: u64| {
+			seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+			(seed >> 33) % bound
+		};
+
+		for _ in 0..200 {
+			let who = next(3) + 1;
+			let other = next(3) + 1;
+			let kitty_id =
+				next(KittiesModule::kitties_count().unwrap_or_default() as u64 + 1) as u32;
+			let auction_id = next(AuctionsCount::<Test>::get() as u64 + 1) as u32;
+			let amount = next(4);
+			let now = System::block_number();
+
+			// failing calls are expected, the reserves must add up either way
+			let _ = match next(12) {
+				0 => KittiesModule::create(Origin::signed(who), amount),
+				1 => KittiesModule::breed(Origin::signed(who), kitty_id, kitty_id + 1, amount),
+				2 => KittiesModule::transfer(Origin::signed(who), other, kitty_id),
+				3 => KittiesModule::list_for_sale(Origin::signed(who), kitty_id, amount),
+				4 => KittiesModule::buy(Origin::signed(who), kitty_id, amount),
+				5 => KittiesModule::create_auction(
+					Origin::signed(who),
+					kitty_id,
+					amount,
+					now + 1 + next(3),
+				),
+				6 => KittiesModule::bid(Origin::signed(who), auction_id, amount),
+				7 => KittiesModule::burn(Origin::signed(who), kitty_id),
+				8 => KittiesModule::approve(Origin::signed(who), kitty_id, other),
+				9 => KittiesModule::transfer_from(Origin::signed(who), other, who, kitty_id),
+				10 => KittiesModule::set_approval_for_all(Origin::signed(who), other, true),
+				_ => {
+					KittiesModule::on_finalize(now);
+					System::set_block_number(now + 1);
+					Ok(())
+				}
+			};
+
+			assert_reserves_match_deposits();
+		}
+	});
+}
+
+#[test]
+fn buy_pays_marketplace_fee_and_creator_royalty() {
+	new_test_ext().execute_with(|| {
+		MarketplaceFee::set(&Permill::from_percent(20));
+		CreatorRoyalty::set(&Permill::from_percent(40));
+		let creator_account_id = 1;
+		let seller_account_id = 2;
+		let buyer_account_id = 3;
+		let kitty_id = 0;
+		let sale_price = 5;
+		assert_ok!(KittiesModule::create(Origin::signed(creator_account_id), 0));
+		assert_ok!(KittiesModule::transfer(
+			Origin::signed(creator_account_id),
+			seller_account_id,
+			kitty_id
+		));
+		assert_ok!(KittiesModule::list_for_sale(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			sale_price
+		));
+		assert_ok!(KittiesModule::buy(Origin::signed(buyer_account_id), kitty_id, sale_price));
+
+		assert_eq!(Balances::free_balance(buyer_account_id), 5);
+		assert_eq!(Balances::free_balance(KittiesModule::account_id()), 1);
+		assert_eq!(Balances::free_balance(creator_account_id), 12);
+		assert_eq!(Balances::free_balance(seller_account_id), 12);
+		assert_eq!(Kitties::<Test>::get(kitty_id).unwrap().creator, creator_account_id);
+		assert_has_event!(Event::<Test>::KittySold(
+			seller_account_id,
+			buyer_account_id,
+			kitty_id,
+			sale_price,
+			1,
+			2
+		));
+	});
+}
+
+#[test]
+fn buy_pays_no_royalty_to_selling_creator() {
+	new_test_ext().execute_with(|| {
+		MarketplaceFee::set(&Permill::from_percent(20));
+		CreatorRoyalty::set(&Permill::from_percent(40));
+		let seller_account_id = 1;
+		let buyer_account_id = 3;
+		let kitty_id = 0;
+		let sale_price = 5;
+		assert_ok!(KittiesModule::create(Origin::signed(seller_account_id), 0));
+		assert_ok!(KittiesModule::list_for_sale(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			sale_price
+		));
+		assert_ok!(KittiesModule::buy(Origin::signed(buyer_account_id), kitty_id, sale_price));
+
+		assert_eq!(Balances::free_balance(buyer_account_id), 5);
+		assert_eq!(Balances::free_balance(KittiesModule::account_id()), 1);
+		assert_eq!(Balances::free_balance(seller_account_id), 14);
+		assert_has_event!(Event::<Test>::KittySold(
+			seller_account_id,
+			buyer_account_id,
+			kitty_id,
+			sale_price,
+			1,
+			0
+		));
+	});
+}
+
+#[test]
+fn auction_pays_marketplace_fee_and_creator_royalty() {
+	new_test_ext().execute_with(|| {
+		MarketplaceFee::set(&Permill::from_percent(20));
+		CreatorRoyalty::set(&Permill::from_percent(40));
+		let creator_account_id = 1;
+		let seller_account_id = 2;
+		let bidder_account_id = 3;
+		let kitty_id = 0;
+		let auction_id = 0;
+		let end_block = 3;
+		assert_ok!(KittiesModule::create(Origin::signed(creator_account_id), 0));
+		assert_ok!(KittiesModule::transfer(
+			Origin::signed(creator_account_id),
+			seller_account_id,
+			kitty_id
+		));
+		assert_ok!(KittiesModule::create_auction(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			1,
+			end_block
+		));
+		assert_ok!(KittiesModule::bid(Origin::signed(bidder_account_id), auction_id, 5));
+
+		KittiesModule::on_finalize(end_block);
+
+		assert_eq!(Owner::<Test>::get(kitty_id), Some(bidder_account_id));
+		assert_eq!(Balances::free_balance(bidder_account_id), 5);
+		assert_eq!(Balances::reserved_balance(bidder_account_id), 0);
+		assert_eq!(Balances::free_balance(KittiesModule::account_id()), 1);
+		assert_eq!(Balances::free_balance(creator_account_id), 12);
+		assert_eq!(Balances::free_balance(seller_account_id), 12);
+		assert_has_event!(Event::<Test>::AuctionSettled(
+			seller_account_id,
+			bidder_account_id,
+			auction_id,
+			kitty_id,
+			5,
+			1,
+			2
+		));
+	});
+}
+
+#[test]
+fn auction_leaves_royalty_of_reaped_creator_to_seller() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(&5);
+		CreatorRoyalty::set(&Permill::from_percent(40));
+		let creator_account_id = 1;
+		let seller_account_id = 2;
+		let bidder_account_id = 3;
+		let kitty_id = 0;
+		let auction_id = 0;
+		let end_block = 3;
+		assert_ok!(KittiesModule::create(Origin::signed(creator_account_id), 0));
+		assert_ok!(KittiesModule::transfer(
+			Origin::signed(creator_account_id),
+			seller_account_id,
+			kitty_id
+		));
+		assert_ok!(Balances::transfer(Origin::signed(creator_account_id), bidder_account_id, 10));
+		assert_eq!(Balances::total_balance(&creator_account_id), 0);
+
+		assert_ok!(KittiesModule::create_auction(
+			Origin::signed(seller_account_id),
+			kitty_id,
+			1,
+			end_block
+		));
+		assert_ok!(KittiesModule::bid(Origin::signed(bidder_account_id), auction_id, 5));
+		KittiesModule::on_finalize(end_block);
+
+		assert_eq!(Owner::<Test>::get(kitty_id), Some(bidder_account_id));
+		assert_eq!(Balances::free_balance(bidder_account_id), 15);
+		assert_eq!(Balances::reserved_balance(bidder_account_id), 0);
+		assert_eq!(Balances::free_balance(seller_account_id), 15);
+		assert_eq!(Balances::total_balance(&creator_account_id), 0);
+		assert_has_event!(Event::<Test>::AuctionSettled(
+			seller_account_id,
+			bidder_account_id,
+			auction_id,
+			kitty_id,
+			5,
+			0,
+			0
+		));
+		assert_eq!(Balances::total_issuance(), 30);
+	});
+}
+
+#[test]
+fn sales_leave_fee_below_existential_deposit_of_unfunded_treasury_to_seller() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(&5);
+		MarketplaceFee::set(&Permill::from_percent(20));
+		let first_account_id = 1;
+		let second_account_id = 2;
+		let kitty_id = 0;
+		let auction_id = 0;
+		let end_block = 3;
+		let treasury = KittiesModule::account_id();
+		assert_eq!(Balances::total_balance(&treasury), 0);
+
+		assert_ok!(KittiesModule::create(Origin::signed(first_account_id), 0));
+		assert_ok!(KittiesModule::list_for_sale(Origin::signed(first_account_id), kitty_id, 5));
+		assert_ok!(KittiesModule::buy(Origin::signed(second_account_id), kitty_id, 5));
+		assert_eq!(Balances::free_balance(first_account_id), 15);
+		assert_has_event!(Event::<Test>::KittySold(
+			first_account_id,
+			second_account_id,
+			kitty_id,
+			5,
+			0,
+			0
+		));
+
+		assert_ok!(KittiesModule::create_auction(
+			Origin::signed(second_account_id),
+			kitty_id,
+			1,
+			end_block
+		));
+		assert_ok!(KittiesModule::bid(Origin::signed(first_account_id), auction_id, 5));
+		KittiesModule::on_finalize(end_block);
+		assert_eq!(Owner::<Test>::get(kitty_id), Some(first_account_id));
+		assert_eq!(Balances::free_balance(second_account_id), 10);
+		assert_has_event!(Event::<Test>::AuctionSettled(
+			second_account_id,
+			first_account_id,
+			auction_id,
+			kitty_id,
+			5,
+			0,
+			0
+		));
+
+		assert_eq!(Balances::total_balance(&treasury), 0);
+		assert_eq!(Balances::total_issuance(), 30);
+	});
+}
+
+#[test]
+fn migrate_records_owner_as_creator() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(2).put::<KittiesModule>();
+
+		let account_id = 1;
+		let kitty_id: u32 = 0;
+		let dna = [7u8; 16];
+		let old_kitty = (dna, 2u64, None::<(u32, u32)>, 0u32, 0u64, (7u8, 7u8, 7u8), 0u64);
+		put_storage_value(
+			b"KittiesModule",
+			b"Kitties",
+			&Blake2_128Concat::hash(&kitty_id.encode()),
+			old_kitty,
+		);
+		Owner::<Test>::insert(kitty_id, Some(account_id));
+
+		KittiesModule::on_runtime_upgrade();
+
+		let kitty = Kitties::<Test>::get(kitty_id).unwrap();
+		assert_eq!((kitty.dna, kitty.price), (dna, 2));
+		assert_eq!(kitty.creator, account_id);
+		assert_eq!(
+			KittiesModule::on_chain_storage_version(),
+			KittiesModule::current_storage_version()
+		);
+	});
+}
+
+#[test]
+fn migrate_rebuilds_owned_kitties() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(3).put::<KittiesModule>();
+
+		Owner::<Test>::insert(0, Some(1));
+		Owner::<Test>::insert(1, Some(2));
+		Owner::<Test>::insert(2, Some(1));
+		Owner::<Test>::insert(3, None::<u64>);
+
+		KittiesModule::on_runtime_upgrade();
+
+		let mut owned = KittiesModule::kitties_of(&1);
+		owned.sort();
+		assert_eq!(owned, vec![0, 2]);
+		assert_eq!(KittiesModule::kitties_of(&2), vec![1]);
+		assert_eq!(
+			KittiesModule::on_chain_storage_version(),
+			KittiesModule::current_storage_version()
+		);
+	});
+}
+
+#[test]
+fn migrate_schedules_reveals_of_pending_kitties() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(4).put::<KittiesModule>();
+		RevealDelay::set(&100);
+		System::set_block_number(120);
+
+		// committed at blocks 50 and 10, the second one can already be revealed
+		for (kitty_id, committed_at) in [(0u32, 50u64), (1, 10)] {
+			let old_pending =
+				(1u64, 2u64, None::<(u32, u32)>, None::<([u8; 16], [u8; 16])>, 0u32, committed_at);
+			put_storage_value(
+				b"KittiesModule",
+				b"PendingKitties",
+				&Blake2_128Concat::hash(&kitty_id.encode()),
+				old_pending,
+			);
+		}
+
+		// the upgrade runs before block 121 is initialized
+		KittiesModule::on_runtime_upgrade();
+
+		assert_eq!(PendingKitties::<Test>::get(0).unwrap().reveal_at, 150);
+		assert_eq!(PendingKitties::<Test>::get(1).unwrap().reveal_at, 121);
+		assert_eq!(RevealsDue::<Test>::get(150), 1);
+		assert_eq!(RevealsDue::<Test>::get(121), 1);
+		assert_eq!(
+			KittiesModule::on_chain_storage_version(),
+			KittiesModule::current_storage_version()
+		);
+
+		System::set_block_number(121);
+		KittiesModule::on_initialize(121);
+		assert_ok!(KittiesModule::reveal(Origin::signed(2), 1));
+		assert_eq!(Owner::<Test>::get(1), Some(1));
+	});
+}
+
+#[test]
+fn max_kitties_owned_is_enforced() {
+	new_test_ext().execute_with(|| {
+		MaxKittiesOwned::set(&2);
+
+		let account_id = 1;
+		let other_account_id = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), 0));
+		assert_ok!(KittiesModule::create(Origin::signed(account_id), 0));
+		assert_eq!(KittiesModule::owned_kitties_count(account_id), 2);
+
+		assert_noop!(
+			KittiesModule::create(Origin::signed(account_id), 0),
+			Error::<Test>::TooManyKitties
+		);
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(account_id), 0, 1, 0),
+			Error::<Test>::TooManyKitties
+		);
+		assert_noop!(
+			<KittiesModule as Mutate<u64>>::mint_into(&2, &account_id),
+			Error::<Test>::TooManyKitties
+		);
+
+		// kitties from others cannot be received either
+		assert_ok!(KittiesModule::create(Origin::signed(other_account_id), 0));
+		assert_noop!(
+			KittiesModule::transfer(Origin::signed(other_account_id), account_id, 2),
+			Error::<Test>::TooManyKitties
+		);
+		assert_ok!(KittiesModule::list_for_sale(Origin::signed(other_account_id), 2, 1));
+		assert_noop!(
+			KittiesModule::buy(Origin::signed(account_id), 2, 1),
+			Error::<Test>::TooManyKitties
+		);
+		assert_ok!(KittiesModule::create_auction(Origin::signed(other_account_id), 2, 1, 5));
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(account_id), 0, 1),
+			Error::<Test>::TooManyKitties
+		);
+
+		// burning a kitty makes room for another one
+		assert_ok!(KittiesModule::burn(Origin::signed(account_id), 0));
+		assert_eq!(KittiesModule::owned_kitties_count(account_id), 1);
+		assert_ok!(KittiesModule::bid(Origin::signed(account_id), 0, 1));
+		KittiesModule::on_finalize(5);
+		assert_eq!(Owner::<Test>::get(2), Some(account_id));
+		assert_eq!(KittiesModule::owned_kitties_count(account_id), 2);
+		assert_eq!(KittiesModule::owned_kitties_count(other_account_id), 0);
+		assert_reserves_match_deposits();
+	});
+}
+
+#[test]
+fn migrate_counts_owned_kitties() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(5).put::<KittiesModule>();
+
+		for (kitty_id, depositor) in [(0u32, 1u64), (1, 2), (2, 1)] {
+			Deposits::<Test>::insert(kitty_id, KittyDeposit { depositor, amount: 0 });
+		}
+
+		KittiesModule::on_runtime_upgrade();
+
+		assert_eq!(KittiesModule::owned_kitties_count(1), 2);
+		assert_eq!(KittiesModule::owned_kitties_count(2), 1);
+		assert_eq!(KittiesModule::owned_kitties_count(3), 0);
+		assert_eq!(KittiesModule::on_chain_storage_version(), 6);
 	});
 }
@@ -31,7 +31,7 @@ pub use frame_support::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 		IdentityFee, Weight,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
 pub use pallet_balances::Call as BalancesCall;
 pub use pallet_timestamp::Call as TimestampCall;
@@ -96,12 +96,12 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// The version of the runtime specification. A full node will not attempt to use its native
 	//   runtime in substitute for the on-chain Wasm runtime unless all of `spec_name`,
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
-	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
-	//   the compatible custom types.
-	spec_version: 100,
+	// This value started at 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
+	//   the compatible custom types, and is bumped with every runtime upgrade.
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 /// This determines the average expected block time that we are targeting.
@@ -240,11 +240,13 @@ impl pallet_timestamp::Config for Runtime {
 parameter_types! {
 	pub const ExistentialDeposit: u128 = 500;
 	pub const MaxLocks: u32 = 50;
+	// Every kitty with a deposit takes one named reserve from its owner.
+	pub const MaxReserves: u32 = 256;
 }
 
 impl pallet_balances::Config for Runtime {
 	type MaxLocks = MaxLocks;
-	type MaxReserves = ();
+	type MaxReserves = MaxReserves;
 	type ReserveIdentifier = [u8; 8];
 	/// The type for recording an account's balance.
 	type Balance = Balance;
@@ -274,12 +276,31 @@ impl pallet_sudo::Config for Runtime {
 	type Call = Call;
 }
 
+parameter_types! {
+	pub const MaxAuctionsPerBlock: u32 = 64;
+	pub const BreedCooldown: BlockNumber = HOURS;
+	// `RandomnessCollectiveFlip` only vouches for randomness from 81 blocks back.
+	pub const RevealDelay: BlockNumber = 100;
+	pub const KittiesPalletId: PalletId = PalletId(*b"py/kitty");
+	pub const MarketplaceFee: Permill = Permill::from_percent(2);
+	pub const CreatorRoyalty: Permill = Permill::from_percent(5);
+	// Each kitty takes a named reserve, see `MaxReserves`.
+	pub const MaxKittiesOwned: u32 = MaxReserves::get();
+}
+
 /// Configure the pallet-kitties in pallets/kitties.
 impl pallet_kitties::Config for Runtime {
 	type Event = Event;
 	type Randomness = RandomnessCollectiveFlip;
 	type Currency = Balances;
 	type KittyIndex = u32;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+	type BreedCooldown = BreedCooldown;
+	type RevealDelay = RevealDelay;
+	type PalletId = KittiesPalletId;
+	type MarketplaceFee = MarketplaceFee;
+	type CreatorRoyalty = CreatorRoyalty;
+	type MaxKittiesOwned = MaxKittiesOwned;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -460,6 +481,12 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_runtime_api::KittiesApi<Block, AccountId, u32> for Runtime {
+		fn kitties_of(owner: AccountId) -> Vec<u32> {
+			KittiesModule::kitties_of(&owner)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (